@@ -1,22 +1,19 @@
-use std::{
-    error::Error,
-    fmt::Debug,
-    io::{self, Write},
-    path::PathBuf,
-    str::FromStr,
-    sync::Arc,
-};
+use std::{error::Error, fmt::Debug, path::PathBuf, str::FromStr, sync::Arc};
 
 use alvidir::{
     document::{lazy::LazyDocument, DocumentRepository},
     id::Identify,
     schema::{
         ops::{delete::Delete, save::Save},
+        transaction::{Background, Changeset, Operation, Transaction},
         Schema,
     },
 };
 use anyhow::Result;
-use clap::{Args, Subcommand};
+use clap::{error::ErrorKind, Args, Subcommand};
+use regex::Regex;
+
+use crate::output::{Change, DocumentId, Output};
 
 /// A file-system document.
 #[derive(Debug, Clone)]
@@ -33,6 +30,16 @@ impl Identify for Document {
     }
 }
 
+#[derive(Args)]
+struct DocumentDeleteArgs {
+    /// Delete all the documents whose id matches the given regular expression instead.
+    #[arg(long, value_parser = Regex::new)]
+    filter: Option<Regex>,
+    /// Print the documents that would be deleted without deleting them.
+    #[arg(long)]
+    preview: bool,
+}
+
 #[derive(Args)]
 struct DocumentSaveArgs {
     /// The content of the node.
@@ -43,7 +50,7 @@ struct DocumentSaveArgs {
 #[clap(subcommand_negates_reqs = true, subcommand_precedence_over_arg = true)]
 enum DocumentSubCommand {
     /// Delete a document.
-    Delete,
+    Delete(DocumentDeleteArgs),
     /// List all documents.
     #[command(alias("ls"))]
     List,
@@ -61,6 +68,27 @@ pub struct DocumentCommand {
     subcommand: DocumentSubCommand,
 }
 
+impl DocumentCommand {
+    /// Checks the conflicts between arguments the parser cannot tell, since they belong to
+    /// different commands.
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if let (
+            Some(_),
+            DocumentSubCommand::Delete(DocumentDeleteArgs {
+                filter: Some(_), ..
+            }),
+        ) = (&self.id, &self.subcommand)
+        {
+            return Err(clap::Error::raw(
+                ErrorKind::ArgumentConflict,
+                "the argument '--filter <FILTER>' cannot be used with '[ID]'",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 pub struct DocumentCli<DocumentRepo>
 where
     DocumentRepo: DocumentRepository,
@@ -68,6 +96,10 @@ where
 {
     pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
     pub document_repo: Arc<DocumentRepo>,
+    /// Whether transactions print their changes instead of committing them.
+    pub dry_run: bool,
+    /// The format in which results are printed.
+    pub output: Output,
 }
 
 impl<DocumentRepo> DocumentCli<DocumentRepo>
@@ -88,15 +120,45 @@ where
         };
 
         match command.subcommand {
-            DocumentSubCommand::Delete => {
-                Delete::new(document_id()?).execute(self.schema.transaction())?
+            DocumentSubCommand::Delete(args) => {
+                let document_ids = match args.filter {
+                    Some(regex) => self
+                        .schema
+                        .read()?
+                        .into_iter()
+                        .map(|node| node.id().clone())
+                        .filter(|id| regex.is_match(&id.to_string_lossy()))
+                        .collect(),
+                    None => vec![document_id()?],
+                };
+
+                if args.preview {
+                    let ids: Vec<_> = document_ids.iter().map(|id| DocumentId(id)).collect();
+                    self.output.print(&ids)?;
+
+                    return Ok(());
+                }
+
+                // Deleting nothing must not fail, not even on read-only documents.
+                if document_ids.is_empty() {
+                    tracing::warn!("no document matches the filter");
+                    return Ok(());
+                }
+
+                self.transaction().with(|ctx| {
+                    document_ids
+                        .into_iter()
+                        .try_for_each(|id| Delete::new(id).execute(ctx.transaction()))
+                })?;
             }
             DocumentSubCommand::List => {
-                let mut stdout = io::stdout().lock();
-                self.schema
-                    .read()
+                let schema = self.schema.read()?;
+                let ids: Vec<_> = schema
                     .into_iter()
-                    .for_each(|node| writeln!(stdout, "{:?}", node.id()).unwrap());
+                    .map(|node| DocumentId(node.id()))
+                    .collect();
+
+                self.output.print(&ids)?;
             }
             DocumentSubCommand::Save(args) => {
                 let document_id = document_id()?;
@@ -106,10 +168,41 @@ where
                 };
 
                 Save::new(LazyDocument::new(self.document_repo.clone(), document))
-                    .execute(self.schema.transaction())?;
+                    .execute(self.transaction())?;
             }
         };
 
         Ok(())
     }
+
+    /// Returns a new transaction over the schema, which is a dry run if so configured.
+    ///
+    /// The changes of the transaction are printed once it completes, unless it is committed in
+    /// text mode, which keeps silent on success.
+    fn transaction(&self) -> Background<'_, LazyDocument<DocumentRepo>> {
+        let report = |changeset: &Changeset<LazyDocument<DocumentRepo>>| {
+            let changes: Vec<_> = changeset
+                .iter()
+                .map(|op| match op {
+                    Operation::Save(node) => Change::Save(DocumentId(node.id())),
+                    Operation::Delete(node_id) => Change::Delete(DocumentId(node_id)),
+                })
+                .collect();
+
+            if let Err(err) = self.output.print(&changes) {
+                tracing::error!(error = err.to_string(), "printing changes");
+            }
+        };
+
+        let transaction = self.schema.transaction();
+        if self.dry_run {
+            return transaction.with_dry_run(report);
+        }
+
+        if self.output == Output::Text {
+            return transaction;
+        }
+
+        transaction.with_report(report)
+    }
 }
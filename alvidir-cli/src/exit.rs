@@ -0,0 +1,49 @@
+//! Process exit codes.
+
+use std::process::ExitCode;
+
+use alvidir::schema::Error;
+
+/// The table of exit codes, as documented in the help text.
+pub const HELP: &str = "\
+Exit codes:
+  0  The command succeeded.
+  1  The command failed for any other reason.
+  2  The arguments are invalid.
+  3  The command targets a document that does not exist.
+  4  The command has been rejected by any of the schema rules.
+  7  The command would modify read-only documents.";
+
+/// The exit code of a failed command, depending on the cause.
+///
+/// Code 2 is reserved for invalid arguments, as reported by the argument parser. Codes 5 and 6 are
+/// reserved for lock contention and corrupt documents, which no command can run into yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Failure {
+    /// Any failure not covered by the rest of variants.
+    Unknown = 1,
+    /// The command targets a document that does not exist.
+    NotFound = 3,
+    /// The command has been rejected by any of the schema rules.
+    Rejected = 4,
+    /// The command would modify a read-only schema.
+    ReadOnly = 7,
+}
+
+impl From<&anyhow::Error> for Failure {
+    fn from(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<Error>() {
+            Some(Error::Noop) => Failure::NotFound,
+            Some(Error::Rejected(_)) => Failure::Rejected,
+            Some(Error::ReadOnly) => Failure::ReadOnly,
+            _ => Failure::Unknown,
+        }
+    }
+}
+
+impl From<Failure> for ExitCode {
+    fn from(failure: Failure) -> Self {
+        ExitCode::from(failure as u8)
+    }
+}
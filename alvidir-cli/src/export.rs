@@ -0,0 +1,121 @@
+//! Graph export command.
+
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository},
+    graph::format::{dot, mermaid},
+    id::Identify,
+    property::Property,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+
+use crate::document::Document;
+
+/// A link from a document to another one.
+///
+/// Links are the relative targets of the Markdown links in the content of the document, resolved
+/// against its directory and with no extension, so they match the ids of documents. Links to
+/// anything not in the graph are kept, and therefore exported as virtual nodes.
+pub struct Link(PathBuf);
+
+impl Identify for Link {
+    type Id = PathBuf;
+
+    fn id(&self) -> &Self::Id {
+        &self.0
+    }
+}
+
+impl<DocumentRepo> Property<LazyDocument<DocumentRepo>> for Link
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn all(source: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+        let Some(document) = source.try_deref() else {
+            return Vec::default();
+        };
+
+        let base = source.id().parent().unwrap_or(Path::new(""));
+        String::from_utf8_lossy(&document.bytes)
+            .split("](")
+            .skip(1)
+            .filter_map(|rest| rest.split_once(')').map(|(target, _)| target))
+            .filter_map(|target| resolve(base, target))
+            .map(Link)
+            .collect()
+    }
+}
+
+/// Returns the id of the document the given link target points to, if any.
+fn resolve(base: &Path, target: &str) -> Option<PathBuf> {
+    // Targets may be followed by a title, and their fragment points inside the document.
+    let target = target.split_whitespace().next()?;
+    let target = target.trim_start_matches('<').trim_end_matches('>');
+    let target = target.split('#').next()?;
+    if target.is_empty() || target.contains(':') {
+        return None;
+    }
+
+    let mut id = PathBuf::new();
+    for component in base.join(target).with_extension("").components() {
+        match component {
+            Component::Normal(name) => id.push(name),
+            Component::ParentDir if !id.pop() => return None,
+            Component::RootDir => id = PathBuf::new(),
+            _ => {}
+        }
+    }
+
+    (!id.as_os_str().is_empty()).then_some(id)
+}
+
+/// The format a graph is exported to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// A Graphviz DOT digraph.
+    #[default]
+    Dot,
+    /// A Mermaid flowchart.
+    Mermaid,
+}
+
+/// Export the graph of documents and their links.
+#[derive(Args)]
+pub struct ExportCommand {
+    /// The format of the exported graph.
+    #[arg(long, value_enum, default_value_t)]
+    format: GraphFormat,
+}
+
+pub struct ExportCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+}
+
+impl<DocumentRepo> ExportCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: ExportCommand) -> Result<()> {
+        let graph = self.schema.read()?;
+        let format_id = |id: &PathBuf| id.to_string_lossy().into_owned();
+        let stdout = io::stdout().lock();
+
+        match command.format {
+            GraphFormat::Dot => dot::write::<_, Link, _>(&graph, format_id, stdout)?,
+            GraphFormat::Mermaid => mermaid::write::<_, Link, _>(&graph, format_id, stdout)?,
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,26 @@
 use clap::Subcommand;
 use document::DocumentCommand;
+use export::ExportCommand;
 
 pub mod document;
+pub mod exit;
+pub mod export;
+pub mod output;
 pub mod repository;
+pub mod timing;
 
 #[derive(Subcommand)]
 pub enum CliCommand {
     Doc(DocumentCommand),
+    Export(ExportCommand),
+}
+
+impl CliCommand {
+    /// Checks the conflicts between arguments the parser cannot tell.
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        match self {
+            CliCommand::Doc(command) => command.validate(),
+            CliCommand::Export(_) => Ok(()),
+        }
+    }
 }
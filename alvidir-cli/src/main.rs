@@ -1,15 +1,29 @@
 use std::{
     ffi::OsString,
-    io,
+    fs, io,
     path::PathBuf,
-    sync::{Arc, LazyLock},
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, LazyLock,
+    },
+    time::{Duration, Instant},
 };
 
 use alvidir::{graph::Graph, schema::Schema};
-use alvidir_cli::{document::DocumentCli, repository::LocalDocumentRepository, CliCommand};
+use alvidir_cli::{
+    document::DocumentCli,
+    exit::{self, Failure},
+    export::ExportCli,
+    output::Output,
+    repository::LocalDocumentRepository,
+    timing::{self, Timings},
+    CliCommand,
+};
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use tracing::Level;
+use tracing_subscriber::{filter::Targets, layer::SubscriberExt, util::SubscriberInitExt};
 
 static DEFAULT_EXTENSION: &str = "md";
 
@@ -24,7 +38,8 @@ static DEFAULT_CONTEXT: LazyLock<OsString> = LazyLock::new(|| {
 #[command(
     name = "alvidir",
     about = "An astonishing graph-based docs manager.",
-    version = "0.0.1"
+    version = "0.0.1",
+    after_help = exit::HELP
 )]
 struct Cli {
     #[command(subcommand)]
@@ -49,33 +64,140 @@ struct Cli {
         long
     )]
     extension: String,
+
+    /// Rejects any command that would modify the documents.
+    #[arg(global = true, long, env = "ALVIDIR_READ_ONLY")]
+    read_only: bool,
+
+    /// Prints the changes a command would make instead of applying them.
+    #[arg(global = true, long)]
+    dry_run: bool,
+
+    /// The format in which results are printed.
+    #[arg(global = true, long, value_enum, default_value_t)]
+    output: Output,
+
+    /// Logs the time spent on each stage of loading the documents.
+    #[arg(global = true, long)]
+    trace: bool,
+
+    /// Prints a summary of the time spent by the command once completed.
+    #[arg(global = true, long)]
+    timings: bool,
+
+    /// Appends the timings of any command slower than the threshold into the given file.
+    #[arg(global = true, long, env = "ALVIDIR_SLOW_LOG")]
+    slow_log: Option<PathBuf>,
+
+    /// The time, in milliseconds, a command must exceed to be written into the slow log.
+    #[arg(global = true, long, value_name = "MILLIS", default_value_t = 1000)]
+    slow_threshold: u64,
 }
 
-#[allow(clippy::arc_with_non_send_sync)]
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let args = Cli::parse();
+    if let Err(err) = args.subcommand.validate() {
+        err.format(&mut Cli::command()).exit();
+    }
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            Failure::from(&err).into()
+        }
+    }
+}
+
+#[allow(clippy::arc_with_non_send_sync)]
+fn run(args: Cli) -> Result<()> {
+    let mut filter = Targets::new().with_default(Level::INFO);
+    if args.trace {
+        filter = filter.with_target(timing::TARGET, Level::TRACE);
+    }
 
     tracing_subscriber::fmt()
         .without_time()
         .with_target(false)
-        .with_max_level(Level::INFO)
+        .with_max_level(Level::TRACE)
         .with_writer(io::stderr)
+        .finish()
+        .with(filter)
         .init();
 
+    let read_only = args.read_only
+        || fs::metadata(&args.context)
+            .map(|metadata| metadata.permissions().readonly())
+            .unwrap_or_default();
+
     let document_repo = Arc::new(LocalDocumentRepository {
         context: args.context,
         extension: args.extension,
     });
 
-    let graph = Graph::from_iter(document_repo.all());
-    let schema = Arc::new(Schema::from(graph));
+    let load = Instant::now();
+    let start = Instant::now();
+    let documents: Vec<_> = document_repo.all().collect();
+    tracing::trace!(
+        target: timing::TARGET,
+        documents = documents.len(),
+        elapsed = ?start.elapsed(),
+        "walking context"
+    );
+
+    let start = Instant::now();
+    let graph = Graph::from_iter(documents);
+    tracing::trace!(target: timing::TARGET, elapsed = ?start.elapsed(), "building graph");
+
+    let records = Arc::new(AtomicUsize::default());
+    let start = Instant::now();
+    let schema = Arc::new(
+        Schema::from(graph)
+            .with_read_only(read_only)
+            .with_subscriber({
+                let records = records.clone();
+                move |changeset, _| {
+                    records.fetch_add(changeset.len(), Ordering::Relaxed);
+                }
+            }),
+    );
+    tracing::trace!(target: timing::TARGET, elapsed = ?start.elapsed(), "building schema");
+
+    let load = load.elapsed();
+    let export_cli = ExportCli {
+        schema: schema.clone(),
+    };
 
     let node_cli = DocumentCli {
         schema,
         document_repo,
+        dry_run: args.dry_run,
+        output: args.output,
     };
 
-    match args.subcommand {
+    let start = Instant::now();
+    let result = match args.subcommand {
         CliCommand::Doc(command) => node_cli.execute(command),
+        CliCommand::Export(command) => export_cli.execute(command),
+    };
+
+    let timings = Timings {
+        load,
+        execution: start.elapsed(),
+        records: records.load(Ordering::Relaxed),
+    };
+
+    if args.timings {
+        eprintln!("{timings}");
     }
+
+    if let Some(path) = args.slow_log {
+        let command = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+        let threshold = Duration::from_millis(args.slow_threshold);
+        if let Err(err) = timings.log_slow(&path, &command, threshold) {
+            tracing::error!(error = err.to_string(), path = ?path, "writing slow log");
+        }
+    }
+
+    result
 }
@@ -0,0 +1,68 @@
+//! Command output formats.
+
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The format in which commands print their results.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    /// One human-readable line per item.
+    #[default]
+    Text,
+    /// A single JSON array with all the items.
+    Json,
+    /// A single YAML sequence with all the items.
+    Yaml,
+}
+
+impl Output {
+    /// Prints the given items into stdout.
+    pub fn print<T>(self, items: &[T]) -> io::Result<()>
+    where
+        T: Display + Serialize,
+    {
+        let mut stdout = io::stdout().lock();
+        match self {
+            Output::Text => items.iter().try_for_each(|item| writeln!(stdout, "{item}")),
+            Output::Json => {
+                serde_json::to_writer(&mut stdout, items)?;
+                writeln!(stdout)
+            }
+            Output::Yaml => serde_yaml::to_writer(&mut stdout, items).map_err(io::Error::other),
+        }
+    }
+}
+
+/// The id of a document.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct DocumentId<'a>(pub &'a Path);
+
+impl Display for DocumentId<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// A change a command would make into the documents.
+#[derive(Debug, Serialize)]
+#[serde(tag = "op", content = "id", rename_all = "lowercase")]
+pub enum Change<'a> {
+    Save(DocumentId<'a>),
+    Delete(DocumentId<'a>),
+}
+
+impl Display for Change<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Save(id) => write!(f, "save {id}"),
+            Change::Delete(id) => write!(f, "delete {id}"),
+        }
+    }
+}
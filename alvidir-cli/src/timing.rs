@@ -0,0 +1,59 @@
+//! Command timing statistics.
+
+use std::{
+    fmt::Display,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The target under which the time spent on each loading stage is traced.
+pub const TARGET: &str = "alvidir::timing";
+
+/// The time spent by a command on each of its stages.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Timings {
+    /// The time spent loading the documents into the schema.
+    pub load: Duration,
+    /// The time spent executing the command itself.
+    pub execution: Duration,
+    /// The amount of committed operations.
+    pub records: usize,
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "load: {:?}, execution: {:?}, total: {:?}, records touched: {}",
+            self.load,
+            self.execution,
+            self.total(),
+            self.records
+        )
+    }
+}
+
+impl Timings {
+    /// Returns the time spent by the command as a whole.
+    pub fn total(&self) -> Duration {
+        self.load + self.execution
+    }
+
+    /// Appends the timings of the given command into the file at path if, and only if, the total
+    /// time exceeds the threshold.
+    pub fn log_slow(&self, path: &Path, command: &str, threshold: Duration) -> io::Result<()> {
+        if self.total() <= threshold {
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{timestamp}\t{command}\t{self}")
+    }
+}
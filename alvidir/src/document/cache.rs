@@ -0,0 +1,251 @@
+//! Read-through caching of documents.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{id::Identify, poison::PoisonPolicy};
+
+use super::DocumentRepository;
+
+/// A least-recently-used set of entries.
+struct Lru<K, V> {
+    /// The entries in the set, alongside their last usage.
+    entries: BTreeMap<K, (u64, V)>,
+    /// The keys of the entries indexed by their last usage.
+    usages: BTreeMap<u64, K>,
+    /// The latest usage.
+    tick: u64,
+}
+
+impl<K, V> Default for Lru<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+            usages: Default::default(),
+            tick: Default::default(),
+        }
+    }
+}
+
+impl<K, V> Lru<K, V>
+where
+    K: Ord + Clone,
+{
+    /// Returns the entry with the given key, if any, marking it as the most recently used.
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.tick += 1;
+
+        let (usage, value) = self.entries.get_mut(key)?;
+        self.usages.remove(usage);
+        self.usages.insert(self.tick, key.clone());
+        *usage = self.tick;
+
+        Some(value)
+    }
+
+    /// Inserts the given entry, evicting the least recently used ones if the capacity is
+    /// exceeded.
+    fn insert(&mut self, key: K, value: V, capacity: usize) {
+        self.remove(&key);
+
+        while self.entries.len() >= capacity {
+            let Some((_, key)) = self.usages.pop_first() else {
+                break;
+            };
+
+            self.entries.remove(&key);
+        }
+
+        if capacity == 0 {
+            return;
+        }
+
+        self.tick += 1;
+        self.usages.insert(self.tick, key.clone());
+        self.entries.insert(key, (self.tick, value));
+    }
+
+    /// Removes the entry with the given key, if any.
+    fn remove(&mut self, key: &K) {
+        if let Some((usage, _)) = self.entries.remove(key) {
+            self.usages.remove(&usage);
+        }
+    }
+}
+
+/// The least-recently-used set of documents from a [`DocumentRepository`].
+type DocumentLru<DocumentRepo> = Lru<
+    <<DocumentRepo as DocumentRepository>::Document as Identify>::Id,
+    <DocumentRepo as DocumentRepository>::Document,
+>;
+
+/// A [`DocumentRepository`] caching the documents retrieved from another one.
+///
+/// Only the `capacity` most recently used documents are kept in memory.
+pub struct CachedDocumentRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    /// The repository being cached.
+    document_repo: DocumentRepo,
+    /// The maximum amount of documents in the cache.
+    capacity: usize,
+    /// The cached documents.
+    cache: Mutex<DocumentLru<DocumentRepo>>,
+    /// How a poisoned cache is handled.
+    poisoning: PoisonPolicy,
+}
+
+impl<DocumentRepo> DocumentRepository for CachedDocumentRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+    DocumentRepo::Document: Clone,
+    <DocumentRepo::Document as Identify>::Id: Ord + Clone,
+{
+    type Document = DocumentRepo::Document;
+
+    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
+        if let Some(document) = self.lock().as_mut().and_then(|cache| cache.get(id)) {
+            return Some(document.clone());
+        }
+
+        let document = self.document_repo.find_by_id(id)?;
+        if let Some(mut cache) = self.lock() {
+            cache.insert(id.clone(), document.clone(), self.capacity);
+        }
+
+        Some(document)
+    }
+}
+
+impl<DocumentRepo> CachedDocumentRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    /// Returns a cache of at most `capacity` documents over the given repository.
+    pub fn new(document_repo: DocumentRepo, capacity: usize) -> Self {
+        Self {
+            document_repo,
+            capacity,
+            cache: Default::default(),
+            poisoning: Default::default(),
+        }
+    }
+
+    /// Sets how a poisoned cache is handled.
+    ///
+    /// Under [`PoisonPolicy::Fail`] a poisoned cache is bypassed, reading every document from the
+    /// underlying repository.
+    pub fn with_poisoning(mut self, poisoning: PoisonPolicy) -> Self {
+        self.poisoning = poisoning;
+        self
+    }
+
+    /// Removes all the documents from the cache.
+    pub fn clear(&self) {
+        if let Some(mut cache) = self.lock() {
+            *cache = Default::default();
+        }
+    }
+
+    fn lock(&self) -> Option<MutexGuard<'_, DocumentLru<DocumentRepo>>> {
+        self.poisoning.apply(self.cache.lock(), "document cache")
+    }
+}
+
+impl<DocumentRepo> CachedDocumentRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+    <DocumentRepo::Document as Identify>::Id: Ord + Clone,
+{
+    /// Removes the document with the given id from the cache, forcing the next read to hit the
+    /// underlying repository.
+    pub fn invalidate(&self, id: &<DocumentRepo::Document as Identify>::Id) {
+        if let Some(mut cache) = self.lock() {
+            cache.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        document::{cache::CachedDocumentRepository, DocumentRepository},
+        id::Identify,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Document(usize);
+
+    impl Identify for Document {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    #[derive(Default)]
+    struct DocumentRepositoryMock {
+        hits: AtomicUsize,
+    }
+
+    impl DocumentRepository for DocumentRepositoryMock {
+        type Document = Document;
+
+        fn find_by_id(&self, id: &usize) -> Option<Self::Document> {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            Some(Document(*id))
+        }
+    }
+
+    #[test]
+    fn cached_documents_should_not_hit_the_repository() {
+        let cache = CachedDocumentRepository::new(DocumentRepositoryMock::default(), 2);
+
+        assert_eq!(cache.find_by_id(&1), Some(Document(1)));
+        assert_eq!(cache.find_by_id(&1), Some(Document(1)));
+        assert_eq!(
+            cache.document_repo.hits.load(Ordering::Relaxed),
+            1,
+            "cached document should not be retrieved twice"
+        );
+
+        cache.invalidate(&1);
+        cache.find_by_id(&1);
+        assert_eq!(
+            cache.document_repo.hits.load(Ordering::Relaxed),
+            2,
+            "invalidated document should be retrieved again"
+        );
+    }
+
+    #[test]
+    fn least_recently_used_documents_should_be_evicted() {
+        let cache = CachedDocumentRepository::new(DocumentRepositoryMock::default(), 2);
+
+        cache.find_by_id(&1);
+        cache.find_by_id(&2);
+        cache.find_by_id(&1);
+        cache.find_by_id(&3);
+        assert_eq!(cache.document_repo.hits.load(Ordering::Relaxed), 3);
+
+        cache.find_by_id(&1);
+        assert_eq!(
+            cache.document_repo.hits.load(Ordering::Relaxed),
+            3,
+            "recently used document should be kept"
+        );
+
+        cache.find_by_id(&2);
+        assert_eq!(
+            cache.document_repo.hits.load(Ordering::Relaxed),
+            4,
+            "least recently used document should be evicted"
+        );
+    }
+}
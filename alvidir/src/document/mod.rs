@@ -2,6 +2,7 @@
 
 use crate::id::Identify;
 
+pub mod cache;
 pub mod lazy;
 
 /// A repository in charge of document's persistance.
@@ -0,0 +1,196 @@
+//! Serde-based codecs for the payload of nodes.
+
+use std::fmt::Display;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::id::Identify;
+
+use super::NodeCodec;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "postcard")]
+    #[error(transparent)]
+    Postcard(#[from] postcard::Error),
+    /// Determines that the decoded payload does not belong to the expected node.
+    #[error("payload belongs to node {0}")]
+    Mismatch(String),
+}
+
+/// A [`NodeCodec`] encoding the payload of nodes as JSON.
+///
+/// The id of each node is expected to be part of its payload, so the decoded node is checked
+/// against the one it has been written for.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonCodec;
+
+impl<T> NodeCodec<T> for JsonCodec
+where
+    T: Identify + Serialize + DeserializeOwned,
+    T::Id: Display,
+{
+    type Payload = str;
+    type Error = Error;
+
+    fn encode_id(&self, id: &T::Id) -> String {
+        id.to_string()
+    }
+
+    fn encode(&self, node: &T) -> Result<String, Self::Error> {
+        serde_json::to_string(node).map_err(Into::into)
+    }
+
+    fn decode(&self, id: &str, payload: &str) -> Result<T, Self::Error> {
+        let node: T = serde_json::from_str(payload)?;
+        if node.id().to_string() != id {
+            return Err(Error::Mismatch(node.id().to_string()));
+        }
+
+        Ok(node)
+    }
+}
+
+/// A [`NodeCodec`] encoding the payload of nodes with postcard, a compact binary format.
+///
+/// As with [`JsonCodec`], the decoded node is checked against the one it has been written for.
+#[cfg(feature = "postcard")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PostcardCodec;
+
+#[cfg(feature = "postcard")]
+impl<T> NodeCodec<T> for PostcardCodec
+where
+    T: Identify + Serialize + DeserializeOwned,
+    T::Id: Display,
+{
+    type Payload = [u8];
+    type Error = Error;
+
+    fn encode_id(&self, id: &T::Id) -> String {
+        id.to_string()
+    }
+
+    fn encode(&self, node: &T) -> Result<Vec<u8>, Self::Error> {
+        postcard::to_allocvec(node).map_err(Into::into)
+    }
+
+    fn decode(&self, id: &str, payload: &[u8]) -> Result<T, Self::Error> {
+        let node: T = postcard::from_bytes(payload)?;
+        if node.id().to_string() != id {
+            return Err(Error::Mismatch(node.id().to_string()));
+        }
+
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        graph::format::{
+            codec::{Error, JsonCodec},
+            NodeCodec,
+        },
+        id::Identify,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Node {
+        id: usize,
+        name: String,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    #[test]
+    fn json_codec_should_check_node_ids() {
+        struct Test {
+            name: &'static str,
+            id: &'static str,
+            payload: &'static str,
+            want: Option<Node>,
+        }
+
+        vec![
+            Test {
+                name: "matching id",
+                id: "1",
+                payload: r#"{"id":1,"name":"one"}"#,
+                want: Some(Node {
+                    id: 1,
+                    name: "one".into(),
+                }),
+            },
+            Test {
+                name: "mismatching id",
+                id: "2",
+                payload: r#"{"id":1,"name":"one"}"#,
+                want: None,
+            },
+            Test {
+                name: "malformed payload",
+                id: "1",
+                payload: r#"{"id":1}"#,
+                want: None,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let got: Result<Node, _> = JsonCodec.decode(test.id, test.payload);
+            assert_eq!(got.ok(), test.want, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn json_codec_should_round_trip() {
+        let node = Node {
+            id: 1,
+            name: "one".into(),
+        };
+
+        let payload = JsonCodec.encode(&node).expect("node should be encoded");
+        let got: Node = JsonCodec
+            .decode(
+                &NodeCodec::<Node>::encode_id(&JsonCodec, &node.id),
+                &payload,
+            )
+            .expect("node should be decoded");
+
+        assert_eq!(got, node, "decoded node should equal the encoded one");
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn postcard_codec_should_round_trip() {
+        use crate::graph::format::codec::PostcardCodec;
+
+        let node = Node {
+            id: 1,
+            name: "one".into(),
+        };
+
+        let payload = PostcardCodec.encode(&node).expect("node should be encoded");
+        let got: Node = PostcardCodec
+            .decode("1", &payload)
+            .expect("node should be decoded");
+
+        assert_eq!(got, node, "decoded node should equal the encoded one");
+
+        let got: Result<Node, _> = PostcardCodec.decode("2", &payload);
+        assert!(
+            matches!(got, Err(Error::Mismatch(_))),
+            "node decoded for another id should be rejected"
+        );
+    }
+}
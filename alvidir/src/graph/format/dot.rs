@@ -0,0 +1,139 @@
+//! Graphviz DOT representation of a graph.
+
+use std::{collections::BTreeSet, io::Write};
+
+use crate::{graph::Graph, id::Identify, property::Property};
+
+use super::Result;
+
+/// Returns the given text as a quoted DOT identifier.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Writes the given graph into the writer as a DOT digraph.
+///
+/// Only ids are written, as formatted by the given closure, since the payload of nodes is of no
+/// use for visualization. Edges pointing to nodes that do not exist in the graph are preserved by
+/// drawing the virtual node dashed.
+pub fn write<T, Edge, W>(
+    graph: &Graph<T>,
+    format_id: impl Fn(&T::Id) -> String,
+    mut writer: W,
+) -> Result<()>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    Edge: Property<T> + Identify<Id = T::Id>,
+    W: Write,
+{
+    writeln!(writer, "digraph {{")?;
+
+    let mut edges = Vec::new();
+    let mut virtual_nodes = BTreeSet::new();
+
+    for node in graph {
+        writeln!(writer, "    {};", quote(&format_id(node.id())))?;
+
+        Edge::all(node).into_iter().for_each(|edge| {
+            if !graph.nodes.contains_key(edge.id()) {
+                virtual_nodes.insert(edge.id().clone());
+            }
+
+            edges.push((node.id().clone(), edge.id().clone()));
+        });
+    }
+
+    for node_id in virtual_nodes {
+        writeln!(
+            writer,
+            "    {} [style=dashed];",
+            quote(&format_id(&node_id))
+        )?;
+    }
+
+    for (source, target) in edges {
+        writeln!(
+            writer,
+            "    {} -> {};",
+            quote(&format_id(&source)),
+            quote(&format_id(&target))
+        )?;
+    }
+
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{
+        format::fixtures::{Edge, Node},
+        Graph,
+    };
+
+    #[test]
+    fn graph_must_be_written() {
+        struct Test {
+            name: &'static str,
+            graph: Graph<Node>,
+            format_id: fn(&usize) -> String,
+            want: &'static str,
+        }
+
+        vec![
+            Test {
+                name: "empty graph",
+                graph: Graph::default(),
+                format_id: usize::to_string,
+                want: "digraph {\n}\n",
+            },
+            Test {
+                name: "graph with virtual nodes",
+                graph: Graph::from_iter(vec![
+                    Node {
+                        id: 1,
+                        edges: vec![2, 3],
+                    },
+                    Node {
+                        id: 2,
+                        edges: vec![1],
+                    },
+                ]),
+                format_id: usize::to_string,
+                want: r#"digraph {
+    "1";
+    "2";
+    "3" [style=dashed];
+    "1" -> "2";
+    "1" -> "3";
+    "2" -> "1";
+}
+"#,
+            },
+            Test {
+                name: "ids to be escaped",
+                graph: Graph::from_iter(vec![Node {
+                    id: 1,
+                    edges: vec![],
+                }]),
+                format_id: |id| format!(r#"say "{id}""#),
+                want: r#"digraph {
+    "say \"1\"";
+}
+"#,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let mut output = Vec::new();
+            super::write::<_, Edge, _>(&test.graph, test.format_id, &mut output)
+                .unwrap_or_else(|err| panic!("{}: graph should be written: {err}", test.name));
+
+            let got = String::from_utf8(output)
+                .unwrap_or_else(|err| panic!("{}: output should be utf-8: {err}", test.name));
+
+            assert_eq!(got, test.want, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,292 @@
+//! GraphML representation of a graph.
+
+use std::{
+    collections::BTreeSet,
+    io::{BufRead, Write},
+};
+
+use quick_xml::{
+    escape::escape,
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::{graph::Graph, id::Identify, property::Property};
+
+use super::{Error, NodeCodec, Result};
+
+/// The name of the key under which the payload of each node is stored.
+const PAYLOAD_KEY: &str = "payload";
+
+/// Writes the given graph into the writer as a GraphML document.
+///
+/// Edges pointing to nodes that do not exist in the graph are preserved by writing the virtual
+/// node with no payload.
+pub fn write<T, Edge, W>(
+    graph: &Graph<T>,
+    codec: &impl NodeCodec<T, Payload = str>,
+    mut writer: W,
+) -> Result<()>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    Edge: Property<T> + Identify<Id = T::Id>,
+    W: Write,
+{
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="{PAYLOAD_KEY}" for="node" attr.name="{PAYLOAD_KEY}" attr.type="string"/>"#
+    )?;
+    writeln!(writer, r#"  <graph id="G" edgedefault="directed">"#)?;
+
+    let mut edges = Vec::new();
+    let mut virtual_nodes = BTreeSet::new();
+
+    for node in graph {
+        let node_id = codec.encode_id(node.id());
+        let payload = codec
+            .encode(node)
+            .map_err(|err| Error::codec(node_id.clone(), err))?;
+
+        writeln!(
+            writer,
+            r#"    <node id="{}"><data key="{PAYLOAD_KEY}">{}</data></node>"#,
+            escape(node_id),
+            escape(payload)
+        )?;
+
+        Edge::all(node).into_iter().for_each(|edge| {
+            if !graph.nodes.contains_key(edge.id()) {
+                virtual_nodes.insert(edge.id().clone());
+            }
+
+            edges.push((node.id().clone(), edge.id().clone()));
+        });
+    }
+
+    for node_id in virtual_nodes {
+        writeln!(
+            writer,
+            r#"    <node id="{}"/>"#,
+            escape(codec.encode_id(&node_id))
+        )?;
+    }
+
+    for (source, target) in edges {
+        writeln!(
+            writer,
+            r#"    <edge source="{}" target="{}"/>"#,
+            escape(codec.encode_id(&source)),
+            escape(codec.encode_id(&target))
+        )?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+
+    Ok(())
+}
+
+/// Reads a graph from the given GraphML document.
+///
+/// The payload of each node is the data under the node key named `payload`, whatever its id is.
+/// Nodes with no payload are virtual and therefore skipped, as well as edges, which are a
+/// property of the payload.
+pub fn read<T, R>(codec: &impl NodeCodec<T, Payload = str>, reader: R) -> Result<Graph<T>>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    R: BufRead,
+{
+    fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+        element
+            .try_get_attribute(name)
+            .map_err(|err| Error::Syntax(err.to_string()))?
+            .map(|attr| {
+                attr.unescape_value()
+                    .map(|value| value.into_owned())
+                    .map_err(|err| Error::Syntax(err.to_string()))
+            })
+            .transpose()
+    }
+
+    fn is_payload(element: &BytesStart, payload_key: Option<&str>) -> Result<bool> {
+        Ok(payload_key.is_some() && attribute(element, "key")?.as_deref() == payload_key)
+    }
+
+    let mut reader = Reader::from_reader(reader);
+    let mut graph = Graph::default();
+
+    let mut buf = Vec::new();
+    let mut payload_key = None;
+    let mut node_id = None;
+    let mut payload: Option<String> = None;
+    let mut in_payload = false;
+
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|err| Error::Syntax(err.to_string()))?
+        {
+            Event::Start(element) | Event::Empty(element) if element.name().as_ref() == b"key" => {
+                let for_nodes = attribute(&element, "for")?
+                    .is_none_or(|domain| domain == "node" || domain == "all");
+
+                if for_nodes && attribute(&element, "attr.name")?.as_deref() == Some(PAYLOAD_KEY) {
+                    payload_key = attribute(&element, "id")?;
+                }
+            }
+            Event::Start(element) if element.name().as_ref() == b"node" => {
+                let Some(id) = attribute(&element, "id")? else {
+                    return Err(Error::Syntax("node with no id".into()));
+                };
+
+                node_id = Some(id);
+                payload = None;
+            }
+            Event::Start(element) if element.name().as_ref() == b"data" => {
+                in_payload = node_id.is_some() && is_payload(&element, payload_key.as_deref())?;
+
+                if in_payload {
+                    payload = Some(String::new());
+                }
+            }
+            Event::Empty(element) if element.name().as_ref() == b"data" => {
+                let in_payload = node_id.is_some() && is_payload(&element, payload_key.as_deref())?;
+
+                if in_payload {
+                    payload = Some(String::new());
+                }
+            }
+            Event::Text(text) if in_payload => {
+                let text = text
+                    .unescape()
+                    .map_err(|err| Error::Syntax(err.to_string()))?;
+
+                payload.get_or_insert_default().push_str(&text);
+            }
+            Event::CData(data) if in_payload => {
+                let data = String::from_utf8(data.into_inner().into_owned())
+                    .map_err(|err| Error::Syntax(err.to_string()))?;
+
+                payload.get_or_insert_default().push_str(&data);
+            }
+            Event::End(element) if element.name().as_ref() == b"data" => {
+                in_payload = false;
+            }
+            Event::End(element) if element.name().as_ref() == b"node" => {
+                let (Some(id), Some(payload)) = (node_id.take(), payload.take()) else {
+                    continue;
+                };
+
+                let node = codec
+                    .decode(&id, &payload)
+                    .map_err(|err| Error::codec(id, err))?;
+
+                graph.insert(node);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            format::fixtures::{Edge, Node, NodeCodecMock},
+            Graph,
+        },
+        id::Identify,
+    };
+
+    #[test]
+    fn graph_must_round_trip() {
+        let graph = Graph::from_iter(vec![
+            Node {
+                id: 1,
+                edges: vec![2, 3],
+            },
+            Node {
+                id: 2,
+                edges: vec![1],
+            },
+        ]);
+
+        let mut output = Vec::new();
+        super::write::<_, Edge, _>(&graph, &NodeCodecMock, &mut output)
+            .expect("graph should be written");
+
+        let document = String::from_utf8(output).expect("document should be utf-8");
+        assert!(
+            document.contains(r#"<node id="3"/>"#),
+            "virtual nodes should be written with no payload"
+        );
+
+        let got = super::read(&NodeCodecMock, document.as_bytes()).expect("graph should be read");
+        let got: Vec<_> = got.into_iter().cloned().collect();
+        let want: Vec<_> = graph.into_iter().cloned().collect();
+        assert_eq!(got, want, "read graph should equal the written one");
+        assert!(got.iter().all(|node| node.id() != &3));
+    }
+
+    #[test]
+    fn payload_must_follow_declared_key() {
+        struct Test<'a> {
+            name: &'a str,
+            document: &'a str,
+            want: Vec<Node>,
+        }
+
+        vec![
+            Test {
+                name: "self-closing payload",
+                document: r#"<graphml>
+                    <key id="payload" for="node" attr.name="payload"/>
+                    <graph><node id="1"><data key="payload"/></node></graph>
+                </graphml>"#,
+                want: vec![Node {
+                    id: 1,
+                    edges: vec![],
+                }],
+            },
+            Test {
+                name: "payload under another key id",
+                document: r#"<graphml>
+                    <key id="d0" for="node" attr.name="payload"/>
+                    <key id="payload" for="node" attr.name="label"/>
+                    <graph><node id="1"><data key="payload">9</data><data key="d0">2</data></node></graph>
+                </graphml>"#,
+                want: vec![Node {
+                    id: 1,
+                    edges: vec![2],
+                }],
+            },
+            Test {
+                name: "undeclared payload key",
+                document: r#"<graphml>
+                    <graph><node id="1"><data key="payload">2</data></node></graph>
+                </graphml>"#,
+                want: vec![],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let got = super::read(&NodeCodecMock, test.document.as_bytes())
+                .unwrap_or_else(|err| panic!("{}: graph should be read: {err}", test.name));
+
+            let got: Vec<_> = got.into_iter().cloned().collect();
+            assert_eq!(got, test.want, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,171 @@
+//! Hexadecimal text adapter for binary codecs.
+
+use crate::id::Identify;
+
+use super::NodeCodec;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error<E> {
+    /// Determines that the adapted codec failed.
+    #[error(transparent)]
+    Codec(E),
+    /// Determines that the payload is not a well-formed hexadecimal string.
+    #[error("malformed hexadecimal payload")]
+    Malformed,
+}
+
+/// A [`NodeCodec`] encoding the binary payloads of the given codec as hexadecimal text.
+///
+/// This allows binary codecs to be used by formats storing payloads as text.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Hex<C>(pub C);
+
+impl<T, C> NodeCodec<T> for Hex<C>
+where
+    T: Identify,
+    C: NodeCodec<T, Payload = [u8]>,
+{
+    type Payload = str;
+    type Error = Error<C::Error>;
+
+    fn encode_id(&self, id: &T::Id) -> String {
+        self.0.encode_id(id)
+    }
+
+    fn encode(&self, node: &T) -> Result<String, Self::Error> {
+        Ok(self
+            .0
+            .encode(node)
+            .map_err(Error::Codec)?
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
+    }
+
+    fn decode(&self, id: &str, payload: &str) -> Result<T, Self::Error> {
+        if !payload.len().is_multiple_of(2) || !payload.bytes().all(|byte| byte.is_ascii_hexdigit())
+        {
+            return Err(Error::Malformed);
+        }
+
+        let bytes = (0..payload.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&payload[index..index + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::Malformed)?;
+
+        self.0.decode(id, &bytes).map_err(Error::Codec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::ParseIntError;
+
+    use crate::graph::format::{fixtures::Node, NodeCodec};
+
+    use super::{Error, Hex};
+
+    /// Encodes the payload of a [`Node`] as one byte per successor.
+    struct BinaryCodecMock;
+
+    impl NodeCodec<Node> for BinaryCodecMock {
+        type Payload = [u8];
+        type Error = ParseIntError;
+
+        fn encode_id(&self, id: &usize) -> String {
+            id.to_string()
+        }
+
+        fn encode(&self, node: &Node) -> Result<Vec<u8>, Self::Error> {
+            Ok(node.edges.iter().map(|&edge| edge as u8).collect())
+        }
+
+        fn decode(&self, id: &str, payload: &[u8]) -> Result<Node, Self::Error> {
+            Ok(Node {
+                id: id.parse()?,
+                edges: payload.iter().map(|&edge| edge as usize).collect(),
+            })
+        }
+    }
+
+    #[test]
+    fn hex_adapter_should_decode_well_formed_payloads() {
+        struct Test {
+            name: &'static str,
+            payload: &'static str,
+            want: Option<Node>,
+        }
+
+        vec![
+            Test {
+                name: "empty payload",
+                payload: "",
+                want: Some(Node {
+                    id: 1,
+                    edges: vec![],
+                }),
+            },
+            Test {
+                name: "well-formed payload",
+                payload: "02ff",
+                want: Some(Node {
+                    id: 1,
+                    edges: vec![2, 255],
+                }),
+            },
+            Test {
+                name: "odd length",
+                payload: "02f",
+                want: None,
+            },
+            Test {
+                name: "non hexadecimal digits",
+                payload: "0g",
+                want: None,
+            },
+            Test {
+                name: "explicit sign",
+                payload: "+f",
+                want: None,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let got = Hex(BinaryCodecMock).decode("1", test.payload);
+            match test.want {
+                Some(want) => assert_eq!(
+                    got.ok(),
+                    Some(want),
+                    "{}: payload should be decoded",
+                    test.name
+                ),
+                None => assert!(
+                    matches!(got, Err(Error::Malformed)),
+                    "{}: got = {got:?}, want = malformed payload",
+                    test.name
+                ),
+            }
+        });
+    }
+
+    #[test]
+    fn hex_adapter_should_round_trip() {
+        let node = Node {
+            id: 1,
+            edges: vec![0, 16, 255],
+        };
+
+        let payload = Hex(BinaryCodecMock)
+            .encode(&node)
+            .expect("node should be encoded");
+
+        assert_eq!(payload, "0010ff", "payload should be hexadecimal");
+
+        let got = Hex(BinaryCodecMock)
+            .decode("1", &payload)
+            .expect("node should be decoded");
+
+        assert_eq!(got, node, "decoded node should equal the encoded one");
+    }
+}
@@ -0,0 +1,171 @@
+//! JSON Graph representation of a graph.
+
+use std::{
+    collections::BTreeSet,
+    io::{Read, Write},
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::{graph::Graph, id::Identify, property::Property};
+
+use super::{Error, NodeCodec, Result};
+
+/// Writes the given graph into the writer as a JSON Graph document.
+///
+/// Edges pointing to nodes that do not exist in the graph are preserved by writing the virtual
+/// node with no metadata.
+pub fn write<T, Edge, W>(
+    graph: &Graph<T>,
+    codec: &impl NodeCodec<T, Payload = str>,
+    writer: W,
+) -> Result<()>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    Edge: Property<T> + Identify<Id = T::Id>,
+    W: Write,
+{
+    let mut nodes = Map::new();
+    let mut edges = Vec::new();
+    let mut virtual_nodes = BTreeSet::new();
+
+    for node in graph {
+        let node_id = codec.encode_id(node.id());
+        let payload = codec
+            .encode(node)
+            .map_err(|err| Error::codec(node_id.clone(), err))?;
+
+        nodes.insert(node_id.clone(), json!({"metadata": {"payload": payload}}));
+
+        Edge::all(node).into_iter().for_each(|edge| {
+            if !graph.nodes.contains_key(edge.id()) {
+                virtual_nodes.insert(edge.id().clone());
+            }
+
+            edges.push(json!({
+                "source": node_id,
+                "target": codec.encode_id(edge.id()),
+            }));
+        });
+    }
+
+    for node_id in virtual_nodes {
+        nodes.insert(codec.encode_id(&node_id), json!({}));
+    }
+
+    let document = json!({
+        "graph": {
+            "directed": true,
+            "nodes": nodes,
+            "edges": edges,
+        }
+    });
+
+    serde_json::to_writer_pretty(writer, &document).map_err(json_error)
+}
+
+/// Reads a graph from the given JSON Graph document.
+///
+/// Nodes with no payload are virtual and therefore skipped, as well as edges, which are a
+/// property of the payload.
+pub fn read<T, R>(codec: &impl NodeCodec<T, Payload = str>, reader: R) -> Result<Graph<T>>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    R: Read,
+{
+    let document: Value = serde_json::from_reader(reader).map_err(json_error)?;
+
+    let Some(nodes) = document
+        .get("graph")
+        .and_then(|graph| graph.get("nodes"))
+        .and_then(Value::as_object)
+    else {
+        return Err(Error::Syntax("missing graph nodes".into()));
+    };
+
+    nodes
+        .iter()
+        .filter_map(|(id, node)| {
+            let payload = node.get("metadata")?.get("payload")?.as_str()?;
+            Some(
+                codec
+                    .decode(id, payload)
+                    .map_err(|err| Error::codec(id.clone(), err)),
+            )
+        })
+        .collect()
+}
+
+/// Tells apart the failures of the underlying reader or writer from malformed documents.
+fn json_error(err: serde_json::Error) -> Error {
+    if err.is_io() {
+        return Error::Io(err.into());
+    }
+
+    Error::Syntax(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use crate::graph::{
+        format::{
+            fixtures::{Edge, Node, NodeCodecMock},
+            Error,
+        },
+        Graph,
+    };
+
+    /// A writer that always fails.
+    struct BrokenWriter;
+
+    impl Write for BrokenWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn graph_must_round_trip() {
+        let graph = Graph::from_iter(vec![
+            Node {
+                id: 1,
+                edges: vec![2, 3],
+            },
+            Node {
+                id: 2,
+                edges: vec![1],
+            },
+        ]);
+
+        let mut output = Vec::new();
+        super::write::<_, Edge, _>(&graph, &NodeCodecMock, &mut output)
+            .expect("graph should be written");
+
+        let got = super::read(&NodeCodecMock, output.as_slice()).expect("graph should be read");
+        let got: Vec<_> = got.into_iter().cloned().collect();
+        let want: Vec<_> = graph.into_iter().cloned().collect();
+        assert_eq!(got, want, "read graph should equal the written one");
+    }
+
+    #[test]
+    fn writer_failures_should_be_io_errors() {
+        let graph = Graph::from_iter(vec![Node {
+            id: 1,
+            edges: vec![],
+        }]);
+
+        let result = super::write::<_, Edge, _>(&graph, &NodeCodecMock, BrokenWriter);
+        assert!(
+            matches!(result, Err(Error::Io(_))),
+            "got = {result:?}, want = Io error"
+        );
+    }
+}
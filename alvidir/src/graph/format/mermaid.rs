@@ -0,0 +1,168 @@
+//! Mermaid flowchart representation of a graph.
+
+use std::{collections::BTreeMap, io::Write};
+
+use crate::{graph::Graph, id::Identify, property::Property};
+
+use super::Result;
+
+/// The class assigned to virtual nodes.
+const VIRTUAL_CLASS: &str = "virtual";
+
+/// Returns the given text as a quoted Mermaid label.
+///
+/// Characters breaking the label are replaced by their entity codes.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    text.chars().for_each(|c| match c {
+        '"' => quoted.push_str("#quot;"),
+        '<' => quoted.push_str("#lt;"),
+        '>' => quoted.push_str("#gt;"),
+        '#' | ';' | '\n' | '\r' => quoted.push_str(&format!("#{};", c as u32)),
+        c => quoted.push(c),
+    });
+
+    quoted.push('"');
+    quoted
+}
+
+/// Writes the given graph into the writer as a Mermaid flowchart.
+///
+/// Since Mermaid restricts the characters of node ids, nodes are named by their position and
+/// labeled with their id, as formatted by the given closure. Edges pointing to nodes that do not
+/// exist in the graph are preserved by drawing the virtual node dashed.
+pub fn write<T, Edge, W>(
+    graph: &Graph<T>,
+    format_id: impl Fn(&T::Id) -> String,
+    mut writer: W,
+) -> Result<()>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    Edge: Property<T> + Identify<Id = T::Id>,
+    W: Write,
+{
+    writeln!(writer, "flowchart LR")?;
+
+    let mut names = BTreeMap::new();
+    let mut edges = Vec::new();
+    let mut virtual_nodes = Vec::new();
+
+    for node in graph {
+        let name = format!("n{}", names.len());
+        writeln!(writer, "    {name}[{}]", quote(&format_id(node.id())))?;
+        names.insert(node.id().clone(), name);
+    }
+
+    for node in graph {
+        for edge in Edge::all(node) {
+            if !names.contains_key(edge.id()) {
+                let name = format!("n{}", names.len());
+                writeln!(writer, "    {name}[{}]", quote(&format_id(edge.id())))?;
+                names.insert(edge.id().clone(), name.clone());
+                virtual_nodes.push(name);
+            }
+
+            edges.push((node.id().clone(), edge.id().clone()));
+        }
+    }
+
+    for (source, target) in edges {
+        writeln!(writer, "    {} --> {}", names[&source], names[&target])?;
+    }
+
+    if !virtual_nodes.is_empty() {
+        writeln!(writer, "    classDef {VIRTUAL_CLASS} stroke-dasharray: 5 5")?;
+        writeln!(
+            writer,
+            "    class {} {VIRTUAL_CLASS}",
+            virtual_nodes.join(",")
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{
+        format::fixtures::{Edge, Node},
+        Graph,
+    };
+
+    #[test]
+    fn graph_must_be_written() {
+        struct Test {
+            name: &'static str,
+            graph: Graph<Node>,
+            format_id: fn(&usize) -> String,
+            want: &'static str,
+        }
+
+        vec![
+            Test {
+                name: "empty graph",
+                graph: Graph::default(),
+                format_id: usize::to_string,
+                want: "flowchart LR\n",
+            },
+            Test {
+                name: "graph with virtual nodes",
+                graph: Graph::from_iter(vec![
+                    Node {
+                        id: 1,
+                        edges: vec![2, 3],
+                    },
+                    Node {
+                        id: 2,
+                        edges: vec![1],
+                    },
+                ]),
+                format_id: usize::to_string,
+                want: r#"flowchart LR
+    n0["1"]
+    n1["2"]
+    n2["3"]
+    n0 --> n1
+    n0 --> n2
+    n1 --> n0
+    classDef virtual stroke-dasharray: 5 5
+    class n2 virtual
+"#,
+            },
+            Test {
+                name: "ids to be escaped",
+                graph: Graph::from_iter(vec![Node {
+                    id: 1,
+                    edges: vec![],
+                }]),
+                format_id: |id| format!(r#"say "{id}""#),
+                want: r#"flowchart LR
+    n0["say #quot;1#quot;"]
+"#,
+            },
+            Test {
+                name: "ids with markup",
+                graph: Graph::from_iter(vec![Node {
+                    id: 1,
+                    edges: vec![],
+                }]),
+                format_id: |id| format!("<b>#{id};</b>\nnext"),
+                want: r##"flowchart LR
+    n0["#lt;b#gt;#35;1#59;#lt;/b#gt;#10;next"]
+"##,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let mut output = Vec::new();
+            super::write::<_, Edge, _>(&test.graph, test.format_id, &mut output)
+                .unwrap_or_else(|err| panic!("{}: graph should be written: {err}", test.name));
+
+            let got = String::from_utf8(output)
+                .unwrap_or_else(|err| panic!("{}: output should be utf-8: {err}", test.name));
+
+            assert_eq!(got, test.want, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,143 @@
+//! Textual representations of a graph.
+
+#[cfg(feature = "serde")]
+pub mod codec;
+pub mod dot;
+#[cfg(feature = "graphml")]
+pub mod graphml;
+pub mod hex;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod mermaid;
+
+use crate::id::Identify;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    /// Determines that the input is not well-formed.
+    #[error("malformed input: {0}")]
+    Syntax(String),
+    /// Determines that a node could not be encoded or decoded.
+    #[error("node {id}: {source}")]
+    Codec {
+        id: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl Error {
+    /// Returns a [`Error::Codec`] for the node with the given id.
+    pub fn codec(id: String, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Codec {
+            id,
+            source: Box::new(source),
+        }
+    }
+}
+
+/// Encodes and decodes the nodes of a graph.
+///
+/// Edges are never decoded, since they are a property of the node's payload.
+///
+/// Payloads may be textual (`str`) or binary (`[u8]`). Formats storing payloads as text, such as
+/// JSON or GraphML, only accept textual codecs; binary ones can be plugged into them through the
+/// [`hex::Hex`] adapter.
+pub trait NodeCodec<T>
+where
+    T: Identify,
+{
+    /// The encoded representation of a node's payload.
+    type Payload: ?Sized + ToOwned;
+
+    /// The error returned when a node cannot be encoded or decoded.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Returns the textual representation of the given id.
+    fn encode_id(&self, id: &T::Id) -> String;
+
+    /// Returns the encoded representation of the given node's payload.
+    fn encode(
+        &self,
+        node: &T,
+    ) -> std::result::Result<<Self::Payload as ToOwned>::Owned, Self::Error>;
+
+    /// Returns the node with the given id and payload.
+    fn decode(&self, id: &str, payload: &Self::Payload) -> std::result::Result<T, Self::Error>;
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+pub(crate) mod fixtures {
+    use std::num::ParseIntError;
+
+    use crate::{id::Identify, property::Property};
+
+    use super::NodeCodec;
+
+    /// A node whose payload is the list of its successors.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Node {
+        pub id: usize,
+        pub edges: Vec<usize>,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    pub struct Edge(usize);
+
+    impl Identify for Edge {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    impl Property<Node> for Edge {
+        fn all(source: &Node) -> Vec<Self> {
+            source.edges.iter().copied().map(Edge).collect()
+        }
+    }
+
+    /// Encodes the payload of a [`Node`] as a comma separated list of ids.
+    pub struct NodeCodecMock;
+
+    impl NodeCodec<Node> for NodeCodecMock {
+        type Payload = str;
+        type Error = ParseIntError;
+
+        fn encode_id(&self, id: &usize) -> String {
+            id.to_string()
+        }
+
+        fn encode(&self, node: &Node) -> Result<String, Self::Error> {
+            Ok(node
+                .edges
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","))
+        }
+
+        fn decode(&self, id: &str, payload: &str) -> Result<Node, Self::Error> {
+            Ok(Node {
+                id: id.parse()?,
+                edges: payload
+                    .split(',')
+                    .filter(|edge| !edge.is_empty())
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?,
+            })
+        }
+    }
+}
@@ -4,6 +4,7 @@ use std::collections::{btree_map::Values, BTreeMap};
 
 use crate::id::Identify;
 
+pub mod format;
 mod proxy;
 pub use proxy::*;
 
@@ -176,3 +177,26 @@ pub mod fixtures {
     #[allow(unused_imports)]
     pub(crate) use fake_node;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph,
+        },
+        id::Identify,
+    };
+
+    #[test]
+    fn graph_iteration_must_be_ordered_by_id() {
+        let graph = Graph::from_iter(vec![fake_node!(3), fake_node!(1), fake_node!(2)]);
+
+        let ids: Vec<_> = graph.into_iter().map(|node| *node.id()).collect();
+        assert_eq!(
+            ids,
+            vec![1, 2, 3],
+            "nodes must be iterated in id order regardless of insertion order"
+        );
+    }
+}
@@ -2,6 +2,7 @@ pub mod deref;
 pub mod document;
 pub mod graph;
 pub mod id;
+pub mod poison;
 pub mod prelude;
 pub mod property;
 pub mod schema;
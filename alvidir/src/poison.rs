@@ -0,0 +1,125 @@
+//! Lock poisoning policies.
+
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc, LockResult,
+};
+
+/// Determines how a poisoned lock is handled.
+///
+/// A lock gets poisoned when a thread panics while holding it, meaning the value behind it may
+/// have been left in an inconsistent state.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PoisonPolicy {
+    /// Refuses to access the poisoned value.
+    Fail,
+    /// Accesses the poisoned value, emitting a warning.
+    #[default]
+    Recover,
+    /// Accesses the poisoned value, emitting a debug event only.
+    RecoverSilently,
+}
+
+impl PoisonPolicy {
+    /// Returns the guard from the given lock result if the policy allows accessing it.
+    ///
+    /// The subject names the value behind the lock in the emitted tracing events.
+    pub fn apply<G>(self, result: LockResult<G>, subject: &str) -> Option<G> {
+        let poisoned = match result {
+            Ok(guard) => return Some(guard),
+            Err(poisoned) => poisoned,
+        };
+
+        match self {
+            PoisonPolicy::Fail => {
+                tracing::error!(subject, "refusing to access poisoned lock");
+                None
+            }
+            PoisonPolicy::Recover => {
+                tracing::warn!(subject, "recovering poisoned lock");
+                Some(poisoned.into_inner())
+            }
+            PoisonPolicy::RecoverSilently => {
+                tracing::debug!(subject, "recovering poisoned lock");
+                Some(poisoned.into_inner())
+            }
+        }
+    }
+}
+
+/// A [`PoisonPolicy`] shared by all the handles cloned from the same one.
+///
+/// Changing the policy of any of the handles changes it for all of them.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedPoisonPolicy(Arc<AtomicU8>);
+
+impl Default for SharedPoisonPolicy {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU8::new(PoisonPolicy::default() as u8)))
+    }
+}
+
+impl SharedPoisonPolicy {
+    /// Returns the current policy.
+    pub(crate) fn get(&self) -> PoisonPolicy {
+        match self.0.load(Ordering::Acquire) {
+            value if value == PoisonPolicy::Fail as u8 => PoisonPolicy::Fail,
+            value if value == PoisonPolicy::RecoverSilently as u8 => PoisonPolicy::RecoverSilently,
+            _ => PoisonPolicy::Recover,
+        }
+    }
+
+    /// Replaces the current policy.
+    pub(crate) fn set(&self, policy: PoisonPolicy) {
+        self.0.store(policy as u8, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::RwLock,
+    };
+
+    use super::PoisonPolicy;
+
+    #[test]
+    fn poisoned_lock_must_follow_policy() {
+        struct Test {
+            name: &'static str,
+            policy: PoisonPolicy,
+            want: Option<usize>,
+        }
+
+        let lock = RwLock::new(1);
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _guard = lock.write();
+            panic!("poisoning the lock");
+        }));
+
+        vec![
+            Test {
+                name: "fail",
+                policy: PoisonPolicy::Fail,
+                want: None,
+            },
+            Test {
+                name: "recover",
+                policy: PoisonPolicy::Recover,
+                want: Some(1),
+            },
+            Test {
+                name: "recover silently",
+                policy: PoisonPolicy::RecoverSilently,
+                want: Some(1),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let got = test.policy.apply(lock.read(), "test").map(|guard| *guard);
+            assert_eq!(got, test.want, "{}", test.name);
+        });
+    }
+}
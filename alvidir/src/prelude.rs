@@ -6,6 +6,7 @@ pub use crate::property::Property;
 pub use crate::schema::{
     ops::{
         delete::{AfterDelete, BeforeDelete},
+        populate::{AfterPopulate, BeforePopulate},
         save::{AfterSave, BeforeSave},
     },
     plugin::Plugin,
@@ -0,0 +1,414 @@
+//! Derived fields of nodes.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+};
+
+use crate::{
+    deref::WithMut,
+    graph::{Graph, Source},
+    id::Identify,
+    property::Property,
+};
+
+use super::{
+    plugin::Plugin,
+    resource::Res,
+    transaction::{Changeset, Operation},
+    Schema,
+};
+
+/// A value computed out of a node and its neighbours.
+pub trait Derive<T>: 'static
+where
+    T: Identify,
+{
+    /// The type of the derived value.
+    type Value: 'static;
+
+    /// Computes the value for the given node.
+    fn derive(node: &T, neighborhood: &Neighborhood<T>) -> Self::Value;
+}
+
+/// The graph around a node whose value is being derived.
+///
+/// Values are recomputed whenever the node or any node linked to it by an edge changes, so they
+/// must not depend on nodes further away.
+pub struct Neighborhood<'a, T>
+where
+    T: Identify,
+{
+    source: &'a dyn Source<Node = T>,
+    referrers: Option<&'a BTreeSet<T::Id>>,
+}
+
+impl<T> Source for Neighborhood<'_, T>
+where
+    T: Identify,
+{
+    type Node = T;
+
+    fn get(&self, id: &T::Id) -> Option<T> {
+        self.source.get(id)
+    }
+
+    fn contains(&self, id: &T::Id) -> bool {
+        self.source.contains(id)
+    }
+}
+
+impl<T> Neighborhood<'_, T>
+where
+    T: Identify,
+{
+    /// Returns all the nodes having an edge to the one being derived.
+    pub fn referrers(&self) -> Vec<T> {
+        self.referrers
+            .into_iter()
+            .flatten()
+            .filter_map(|node_id| self.source.get(node_id))
+            .collect()
+    }
+}
+
+/// The resource holding the values of a [`Derive`] implementation for each node in the schema.
+pub struct Derived<T, D>
+where
+    T: Identify,
+    D: Derive<T>,
+{
+    values: BTreeMap<T::Id, D::Value>,
+    /// The ids of the nodes having an edge to each node.
+    referrers: BTreeMap<T::Id, BTreeSet<T::Id>>,
+    /// The ids of the nodes whose value must be recomputed once the graph is written.
+    outdated: BTreeSet<T::Id>,
+}
+
+impl<T, D> Default for Derived<T, D>
+where
+    T: Identify,
+    D: Derive<T>,
+{
+    fn default() -> Self {
+        Self {
+            values: Default::default(),
+            referrers: Default::default(),
+            outdated: Default::default(),
+        }
+    }
+}
+
+impl<T, D> Derived<T, D>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    D: Derive<T>,
+{
+    /// Returns the derived value of the node with the given id, if any.
+    pub fn get(&self, node_id: &T::Id) -> Option<&D::Value> {
+        self.values.get(node_id)
+    }
+}
+
+impl<T, D> Derived<T, D>
+where
+    T: Identify + Clone,
+    T::Id: Ord + Clone,
+    D: Derive<T>,
+{
+    /// Registers the edges of the given node, returning the ids they point to.
+    fn link<Edge>(&mut self, node: &T) -> Vec<T::Id>
+    where
+        Edge: Property<T> + Identify<Id = T::Id>,
+    {
+        Edge::all(node)
+            .into_iter()
+            .map(|edge| {
+                self.referrers
+                    .entry(edge.id().clone())
+                    .or_default()
+                    .insert(node.id().clone());
+
+                edge.id().clone()
+            })
+            .collect()
+    }
+
+    /// Unregisters the edges of the given node, returning the ids they pointed to.
+    fn unlink<Edge>(&mut self, node: &T) -> Vec<T::Id>
+    where
+        Edge: Property<T> + Identify<Id = T::Id>,
+    {
+        Edge::all(node)
+            .into_iter()
+            .map(|edge| {
+                if let Some(referrers) = self.referrers.get_mut(edge.id()) {
+                    referrers.remove(node.id());
+                    if referrers.is_empty() {
+                        self.referrers.remove(edge.id());
+                    }
+                }
+
+                edge.id().clone()
+            })
+            .collect()
+    }
+
+    /// Recomputes the value of the node with the given id, dropping it if the node does not
+    /// exist.
+    fn derive(&mut self, node_id: T::Id, source: &dyn Source<Node = T>) {
+        let Some(node) = source.get(&node_id) else {
+            self.values.remove(&node_id);
+            return;
+        };
+
+        let neighborhood = Neighborhood {
+            source,
+            referrers: self.referrers.get(&node_id),
+        };
+
+        let value = D::derive(&node, &neighborhood);
+        self.values.insert(node_id, value);
+    }
+
+    /// Registers the edges of the given changeset before it is applied into the given graph,
+    /// marking the changed nodes as outdated, as well as the ones linked to them.
+    fn stage<Edge>(&mut self, changeset: &Changeset<T>, graph: &Graph<T>)
+    where
+        Edge: Property<T> + Identify<Id = T::Id>,
+    {
+        // The last operation of each node is the one that determines its final state.
+        let changes: BTreeMap<_, _> = changeset.iter().map(|op| (op.id(), op)).collect();
+
+        changes.iter().for_each(|(&node_id, op)| {
+            self.outdated.insert(node_id.clone());
+
+            if let Some(node) = graph.get(node_id) {
+                let outdated = self.unlink::<Edge>(&node);
+                self.outdated.extend(outdated);
+            }
+
+            if let Operation::Save(node) = op {
+                let outdated = self.link::<Edge>(node);
+                self.outdated.extend(outdated);
+            }
+        });
+
+        changes.keys().for_each(|&node_id| {
+            if let Some(referrers) = self.referrers.get(node_id) {
+                self.outdated.extend(referrers.iter().cloned());
+            }
+        });
+    }
+
+    /// Recomputes the values of all the outdated nodes from the given graph, which must already
+    /// include the staged changes.
+    fn apply(&mut self, graph: &Graph<T>) {
+        std::mem::take(&mut self.outdated)
+            .into_iter()
+            .for_each(|node_id| self.derive(node_id, graph));
+    }
+}
+
+/// Implements the [`Plugin`] trait for keeping the [`Derived`] values of D up to date, following
+/// the edges of type Edge to find the neighbours of each node.
+///
+/// Values are recomputed right after the changes of a committed transaction are written into the
+/// graph, never before, so aborted transactions cannot leave stale values behind.
+pub struct DerivePlugin<D, Edge> {
+    derive: PhantomData<D>,
+    edge: PhantomData<Edge>,
+}
+
+impl<D, Edge> Default for DerivePlugin<D, Edge> {
+    fn default() -> Self {
+        Self {
+            derive: PhantomData,
+            edge: PhantomData,
+        }
+    }
+}
+
+impl<T, D, Edge> Plugin<T> for DerivePlugin<D, Edge>
+where
+    T: 'static + Identify + Clone,
+    T::Id: Ord + Clone,
+    D: Derive<T>,
+    Edge: 'static + Property<T> + Identify<Id = T::Id>,
+{
+    fn install(self, schema: Schema<T>) -> Schema<T>
+    where
+        T: Identify,
+    {
+        let mut derived = Derived::<T, D>::default();
+        // A graph refusing to be read has already been reported by its poison policy.
+        if let Ok(graph) = schema.read() {
+            graph.into_iter().for_each(|node| {
+                derived.link::<Edge>(node);
+            });
+
+            graph.into_iter().for_each(|node| {
+                derived.derive(node.id().clone(), &*graph);
+            });
+        }
+
+        let schema = schema.with_resource(derived);
+        let staged = Res::<Derived<T, D>>::from(schema.resources());
+        let derived = Res::<Derived<T, D>>::from(schema.resources());
+
+        schema
+            .with_subscriber(move |changeset, graph| {
+                staged.with_mut(|derived| derived.stage::<Edge>(changeset, graph));
+            })
+            .with_observer(move |graph| {
+                derived.with_mut(|derived| derived.apply(graph));
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deref::With,
+        graph::{
+            fixtures::{fake_node, FakeEdge, FakeNode},
+            Graph,
+        },
+        id::Identify,
+        schema::{
+            derive::{Derive, DerivePlugin, Derived, Neighborhood},
+            resource::Res,
+            transaction::Transaction,
+            Error, Result, Schema,
+        },
+    };
+
+    type Node = FakeNode<'static, usize>;
+
+    struct Degree;
+
+    impl Derive<Node> for Degree {
+        type Value = usize;
+
+        fn derive(node: &Node, _: &Neighborhood<Node>) -> Self::Value {
+            node.edges_fn.map(|edges| edges().len()).unwrap_or_default()
+        }
+    }
+
+    struct Referrers;
+
+    impl Derive<Node> for Referrers {
+        type Value = Vec<usize>;
+
+        fn derive(_: &Node, neighborhood: &Neighborhood<Node>) -> Self::Value {
+            neighborhood
+                .referrers()
+                .iter()
+                .map(|node| *node.id())
+                .collect()
+        }
+    }
+
+    fn derived<D>(schema: &Schema<Node>, node_id: usize) -> Option<D::Value>
+    where
+        D: Derive<Node>,
+        D::Value: Clone,
+    {
+        Res::<Derived<Node, D>>::from(schema.resources())
+            .with(|derived| derived.get(&node_id).cloned())
+            .flatten()
+    }
+
+    #[test]
+    fn derived_values_should_follow_committed_changes() {
+        let schema = Schema::from(Graph::default().with_node(fake_node!(1, 2)))
+            .install(DerivePlugin::<Degree, FakeEdge<usize>>::default());
+
+        assert_eq!(
+            derived::<Degree>(&schema, 1),
+            Some(1),
+            "existing nodes should be derived on install"
+        );
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(1)?;
+                ctx.save(fake_node!(2, 1))?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            derived::<Degree>(&schema, 1),
+            None,
+            "deleted nodes should be dropped"
+        );
+
+        assert_eq!(
+            derived::<Degree>(&schema, 2),
+            Some(1),
+            "saved nodes should be derived"
+        );
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.save(fake_node!(3, 1))?;
+                Result::<()>::Err(Error::custom("failed transaction"))
+            })
+            .expect_err("transaction error should be propagated");
+
+        assert_eq!(
+            derived::<Degree>(&schema, 3),
+            None,
+            "uncommitted nodes should not be derived"
+        );
+    }
+
+    #[test]
+    fn derived_values_should_follow_neighbour_changes() {
+        let schema = Schema::from(
+            Graph::default()
+                .with_node(fake_node!(1, 2))
+                .with_node(fake_node!(2)),
+        )
+        .install(DerivePlugin::<Referrers, FakeEdge<usize>>::default());
+
+        assert_eq!(
+            derived::<Referrers>(&schema, 2),
+            Some(vec![1]),
+            "existing referrers should be derived on install"
+        );
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.save(fake_node!(3, 2))?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            derived::<Referrers>(&schema, 2),
+            Some(vec![1, 3]),
+            "new referrers should update untouched nodes"
+        );
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.save(fake_node!(1))?;
+                ctx.delete(3)?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            derived::<Referrers>(&schema, 2),
+            Some(Vec::default()),
+            "dropped and deleted referrers should update untouched nodes"
+        );
+    }
+}
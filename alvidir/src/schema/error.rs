@@ -9,6 +9,15 @@ pub enum Error {
     /// Determines that an operation has no effect.
     #[error("nothing to apply")]
     Noop,
+    /// Determines that the schema does not accept mutations.
+    #[error("read-only schema")]
+    ReadOnly,
+    /// Determines that the schema is poisoned and its policy refuses to access it.
+    #[error("poisoned schema")]
+    Poisoned,
+    /// Determines that a rule of the schema refuses the operation.
+    #[error("{0}")]
+    Rejected(String),
     #[error("{0}")]
     Msg(String),
 }
@@ -21,4 +30,12 @@ impl Error {
     {
         Self::Msg(msg.to_string())
     }
+
+    /// Returns a rejection with the given message as cause.
+    pub fn rejected<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self::Rejected(msg.to_string())
+    }
 }
@@ -7,7 +7,7 @@ use std::{
 
 use crate::{graph::Graph, id::Identify};
 
-use super::Schema;
+use super::{Error, Schema};
 
 /// A read-only access to a schema.
 pub struct SchemaReadGuard<'a, T>
@@ -28,20 +28,18 @@ where
     }
 }
 
-impl<'a, T> From<&'a Schema<T>> for SchemaReadGuard<'a, T>
+impl<'a, T> TryFrom<&'a Schema<T>> for SchemaReadGuard<'a, T>
 where
     T: Identify,
 {
-    fn from(schema: &'a Schema<T>) -> Self {
-        SchemaReadGuard {
-            guard: match schema.graph.read() {
-                Ok(graph) => graph,
-                Err(poisoned) => {
-                    tracing::error!(error = poisoned.to_string(), "posioned graph");
-                    poisoned.into_inner()
-                }
-            },
-        }
+    type Error = Error;
+
+    fn try_from(schema: &'a Schema<T>) -> Result<Self, Self::Error> {
+        schema
+            .poisoning
+            .apply(schema.graph.read(), "graph")
+            .map(|guard| SchemaReadGuard { guard })
+            .ok_or(Error::Poisoned)
     }
 }
 
@@ -73,19 +71,17 @@ where
     }
 }
 
-impl<'a, T> From<&'a Schema<T>> for SchemaWriteGuard<'a, T>
+impl<'a, T> TryFrom<&'a Schema<T>> for SchemaWriteGuard<'a, T>
 where
     T: Identify,
 {
-    fn from(schema: &'a Schema<T>) -> Self {
-        SchemaWriteGuard {
-            guard: match schema.graph.write() {
-                Ok(graph) => graph,
-                Err(poisoned) => {
-                    tracing::error!(error = poisoned.to_string(), "posioned graph");
-                    poisoned.into_inner()
-                }
-            },
-        }
+    type Error = Error;
+
+    fn try_from(schema: &'a Schema<T>) -> Result<Self, Self::Error> {
+        schema
+            .poisoning
+            .apply(schema.graph.write(), "graph")
+            .map(|guard| SchemaWriteGuard { guard })
+            .ok_or(Error::Poisoned)
     }
 }
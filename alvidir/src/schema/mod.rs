@@ -1,10 +1,12 @@
 //! Schema representation.
 
+pub mod derive;
 mod error;
 pub use error::{Error, Result};
 pub mod guard;
 pub mod ops;
 pub mod plugin;
+pub mod registry;
 pub mod resource;
 pub mod transaction;
 pub mod trigger;
@@ -12,12 +14,13 @@ pub mod trigger;
 use std::sync::RwLock;
 
 use guard::{SchemaReadGuard, SchemaWriteGuard};
+use ops::populate::Populate;
 use plugin::Plugin;
 use resource::ResourceSet;
-use transaction::Background;
+use transaction::{Background, Changeset, Observer, Subscriber};
 use trigger::{Trigger, TriggerSet};
 
-use crate::{graph::Graph, id::Identify};
+use crate::{graph::Graph, id::Identify, poison::PoisonPolicy};
 
 /// A graph that is subject to a set of rules.
 pub struct Schema<T>
@@ -30,6 +33,16 @@ where
     resources: ResourceSet,
     /// All the triggers in the schema.
     triggers: TriggerSet<T>,
+    /// Whether the schema rejects any transaction or not.
+    read_only: bool,
+    /// Whether committed operations are compacted or not.
+    compaction: bool,
+    /// All the subscribers to committed changesets.
+    subscribers: Vec<Subscriber<T>>,
+    /// All the observers of the graph once changesets are applied.
+    observers: Vec<Observer<T>>,
+    /// How poisoned locks are handled.
+    poisoning: PoisonPolicy,
 }
 
 impl<T> From<Graph<T>> for Schema<T>
@@ -41,6 +54,11 @@ where
             graph: RwLock::new(graph),
             resources: Default::default(),
             triggers: Default::default(),
+            read_only: false,
+            compaction: true,
+            subscribers: Default::default(),
+            observers: Default::default(),
+            poisoning: Default::default(),
         }
     }
 }
@@ -83,6 +101,88 @@ where
         self
     }
 
+    /// Sets whether the schema is read-only or not.
+    ///
+    /// A read-only schema rejects all transactions before acquiring any lock, except for dry runs,
+    /// which only read the graph.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Returns true if, and only if, the schema is read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Sets whether committed operations are compacted or not.
+    ///
+    /// A compacting schema keeps only the last operation of each node when committing a
+    /// transaction, which leads to the same result. Compaction is enabled by default.
+    pub fn with_compaction(mut self, compaction: bool) -> Self {
+        self.compaction = compaction;
+        self
+    }
+
+    /// Returns true if, and only if, the schema compacts committed operations.
+    pub fn is_compacting(&self) -> bool {
+        self.compaction
+    }
+
+    /// Subscribes the given closure to the changeset of every committed transaction.
+    ///
+    /// Subscribers are notified while the schema is still locked, hence they must not access it.
+    /// Instead, they are given the graph the changeset is about to be applied to.
+    pub fn with_subscriber<F>(mut self, subscriber: F) -> Self
+    where
+        F: Fn(&Changeset<T>, &Graph<T>) + 'static,
+    {
+        self.subscribers.push(Box::new(subscriber));
+        self
+    }
+
+    /// Returns the subscribers of this schema.
+    pub fn subscribers(&self) -> &[Subscriber<T>] {
+        &self.subscribers
+    }
+
+    /// Notifies the given closure with the graph every committed changeset has just been applied
+    /// to.
+    ///
+    /// Observers are notified after subscribers, while the schema is still locked, hence they must
+    /// not access it either.
+    pub fn with_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&Graph<T>) + 'static,
+    {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Returns the observers of this schema.
+    pub fn observers(&self) -> &[Observer<T>] {
+        &self.observers
+    }
+
+    /// Sets how poisoned locks in the schema, its transactions and resources are handled.
+    ///
+    /// Under [`PoisonPolicy::Fail`] reading, writing or running transactions over a poisoned graph
+    /// fails with [`Error::Poisoned`]. The policy also applies to the resource handles already
+    /// taken out of the schema, such as the ones held by installed plugins.
+    ///
+    /// Until this is called, the graph, transactions and resources follow
+    /// [`PoisonPolicy::default`].
+    pub fn with_poisoning(mut self, poisoning: PoisonPolicy) -> Self {
+        self.poisoning = poisoning;
+        self.resources = self.resources.with_poisoning(poisoning);
+        self
+    }
+
+    /// Returns the poison policy of this schema.
+    pub fn poisoning(&self) -> PoisonPolicy {
+        self.poisoning
+    }
+
     /// Returns the resource set of this schema.
     pub fn resources(&self) -> &ResourceSet {
         &self.resources
@@ -93,19 +193,37 @@ where
         &self.triggers
     }
 
+    /// Bulk-loads the given nodes into the schema within a single transaction.
+    ///
+    /// See [`Populate`] for details.
+    pub fn populate<I>(&self, nodes: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: 'static + Clone,
+        T::Id: Clone + Ord,
+    {
+        Populate::new(nodes).execute(self.transaction())
+    }
+
     /// Returns a new transaction background.
     #[inline]
     pub fn transaction(&self) -> Background<'_, T> {
         self.into()
     }
 
+    /// Acquires a read-only access to the graph.
+    ///
+    /// Fails with [`Error::Poisoned`] if the graph is poisoned and the policy refuses to access it.
     #[inline]
-    pub fn read(&self) -> SchemaReadGuard<'_, T> {
-        self.into()
+    pub fn read(&self) -> Result<SchemaReadGuard<'_, T>> {
+        self.try_into()
     }
 
+    /// Acquires a read-write access to the graph.
+    ///
+    /// Fails with [`Error::Poisoned`] if the graph is poisoned and the policy refuses to access it.
     #[inline]
-    pub fn write(&self) -> SchemaWriteGuard<'_, T> {
-        self.into()
+    pub fn write(&self) -> Result<SchemaWriteGuard<'_, T>> {
+        self.try_into()
     }
 }
@@ -38,7 +38,7 @@ where
 
             let ctx = ctx.with_target(node);
             ctx.triggers().select(BeforeDelete).execute(&ctx)?;
-            ctx.delete(self.node_id);
+            ctx.delete(self.node_id)?;
             ctx.triggers().select(AfterDelete).execute(&ctx)?;
 
             Ok(())
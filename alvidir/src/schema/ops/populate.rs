@@ -0,0 +1,155 @@
+//! Populate transaction.
+
+use crate::{
+    deref::With,
+    id::Identify,
+    prelude::Transaction,
+    schema::{ops::save::BeforeSave, trigger::Trigger, Error, Result},
+};
+
+/// Schedules a trigger before a population is performed.
+pub struct BeforePopulate;
+
+/// Schedules a trigger after a population is performed.
+pub struct AfterPopulate;
+
+/// A bulk-load transaction of nodes into a schema.
+///
+/// Unlike saving each node one by one, populating fires a single aggregate event for all the
+/// nodes, instead of one per node. Yet every node goes through the [`BeforeSave`] triggers, so
+/// populating never bypasses the validations of a regular save.
+pub struct Populate<I> {
+    /// The nodes being loaded into the schema.
+    pub nodes: I,
+}
+
+impl<I> Populate<I> {
+    /// Executes the [`Populate`] transaction.
+    pub fn execute<T>(self, tx: impl Transaction<Target = T>) -> Result<()>
+    where
+        T: 'static + Identify + Clone,
+        I: IntoIterator<Item = T>,
+    {
+        tx.with(|ctx| {
+            ctx.triggers().select(BeforePopulate).execute(&ctx)?;
+            self.nodes.into_iter().try_for_each(|node| {
+                ctx.transaction().with(|ctx| {
+                    let ctx = ctx.with_target(node);
+                    ctx.triggers().select(BeforeSave).execute(&ctx)?;
+                    ctx.target()
+                        .with(|node| ctx.save(node.clone()))
+                        .unwrap_or(Err(Error::Poisoned))?;
+
+                    Ok(())
+                })
+            })?;
+            ctx.triggers().select(AfterPopulate).execute(&ctx)?;
+
+            Ok(())
+        })
+    }
+}
+
+impl<I> Populate<I> {
+    pub fn new(nodes: I) -> Self {
+        Self { nodes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        deref::With,
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph, Source,
+        },
+        id::Identify,
+        schema::{
+            ops::{
+                populate::{AfterPopulate, Populate},
+                save::{AfterSave, BeforeSave},
+            },
+            transaction::{Ctx, Target},
+            Error, Result, Schema,
+        },
+    };
+
+    type Node = FakeNode<'static, usize>;
+
+    #[test]
+    fn populate_should_fire_a_single_event() {
+        static POPULATES: AtomicUsize = AtomicUsize::new(0);
+        static SAVES: AtomicUsize = AtomicUsize::new(0);
+
+        fn on_populate(_: Ctx<Node>) -> Result<()> {
+            POPULATES.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn on_save(_: Ctx<Node>) -> Result<()> {
+            SAVES.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        let schema = Schema::from(Graph::default())
+            .with_trigger(AfterPopulate, on_populate)
+            .with_trigger(AfterSave, on_save);
+
+        Populate::new(vec![fake_node!(1), fake_node!(2), fake_node!(3)])
+            .execute(schema.transaction())
+            .expect("populate should not fail");
+
+        assert_eq!(
+            POPULATES.load(Ordering::Relaxed),
+            1,
+            "populate should fire a single aggregate event"
+        );
+
+        assert_eq!(
+            SAVES.load(Ordering::Relaxed),
+            0,
+            "populate should not fire per-node save events"
+        );
+
+        assert_eq!(
+            schema
+                .read()
+                .expect("graph should be readable")
+                .into_iter()
+                .count(),
+            3,
+            "all the nodes should be saved"
+        );
+    }
+
+    #[test]
+    fn populate_should_validate_every_node() {
+        fn reject_even_ids(_: Ctx<Node>, target: Target<Node>) -> Result<()> {
+            match target.with(|node| node.id() % 2 == 0) {
+                Some(true) => Err(Error::rejected("even ids are not allowed")),
+                _ => Ok(()),
+            }
+        }
+
+        let schema = Schema::from(Graph::default()).with_trigger(BeforeSave, reject_even_ids);
+
+        let result = Populate::new(vec![fake_node!(1), fake_node!(2), fake_node!(3)])
+            .execute(schema.transaction());
+
+        assert!(
+            matches!(result, Err(Error::Rejected(_))),
+            "populate should be rejected by save-time validations"
+        );
+
+        assert!(
+            !schema
+                .read()
+                .expect("graph should be readable")
+                .contains(&1),
+            "rejected populate should not save any node"
+        );
+    }
+}
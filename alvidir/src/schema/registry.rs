@@ -0,0 +1,216 @@
+//! Registry of independent schemas.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::{id::Identify, poison::PoisonPolicy};
+
+use super::{Error, Result, Schema};
+
+/// A set of independent schemas identified by name.
+///
+/// Each schema keeps its own graph, resources and triggers, so nodes sharing the same id in
+/// different schemas never interfere with each other.
+pub struct SchemaRegistry<Name, T>
+where
+    T: Identify,
+{
+    /// All the schemas in the registry.
+    schemas: RwLock<BTreeMap<Name, Arc<Schema<T>>>>,
+    /// How a poisoned registry is handled.
+    poisoning: PoisonPolicy,
+}
+
+impl<Name, T> Default for SchemaRegistry<Name, T>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            schemas: Default::default(),
+            poisoning: Default::default(),
+        }
+    }
+}
+
+impl<Name, T> SchemaRegistry<Name, T>
+where
+    Name: Ord,
+    T: Identify,
+{
+    /// Registers the given schema under the given name.
+    ///
+    /// If the name already exists, the old schema is overwritten.
+    pub fn with_schema(self, name: Name, schema: Schema<T>) -> Result<Self> {
+        self.insert(name, schema)?;
+        Ok(self)
+    }
+
+    /// Sets how a poisoned registry is handled.
+    pub fn with_poisoning(mut self, poisoning: PoisonPolicy) -> Self {
+        self.poisoning = poisoning;
+        self
+    }
+
+    /// Registers the given schema under the given name, returning the old one, if any.
+    ///
+    /// Fails with [`Error::Poisoned`] if the registry is poisoned and its policy refuses to access
+    /// it, in which case the given schema is dropped.
+    pub fn insert(&self, name: Name, schema: Schema<T>) -> Result<Option<Arc<Schema<T>>>> {
+        Ok(self
+            .poisoning
+            .apply(self.schemas.write(), "schema registry")
+            .ok_or(Error::Poisoned)?
+            .insert(name, Arc::new(schema)))
+    }
+
+    /// Returns the schema with the given name, if any.
+    ///
+    /// Fails with [`Error::Poisoned`] if the registry is poisoned and its policy refuses to access
+    /// it.
+    pub fn get(&self, name: &Name) -> Result<Option<Arc<Schema<T>>>> {
+        Ok(self
+            .poisoning
+            .apply(self.schemas.read(), "schema registry")
+            .ok_or(Error::Poisoned)?
+            .get(name)
+            .cloned())
+    }
+
+    /// Unregisters the schema with the given name, returning it, if any.
+    ///
+    /// Handles retrieved before removing the schema remain valid. Fails with [`Error::Poisoned`]
+    /// if the registry is poisoned and its policy refuses to access it, in which case nothing is
+    /// removed.
+    pub fn remove(&self, name: &Name) -> Result<Option<Arc<Schema<T>>>> {
+        Ok(self
+            .poisoning
+            .apply(self.schemas.write(), "schema registry")
+            .ok_or(Error::Poisoned)?
+            .remove(name))
+    }
+
+    /// Returns true if, and only if, a schema with the given name exists.
+    pub fn contains(&self, name: &Name) -> bool {
+        self.poisoning
+            .apply(self.schemas.read(), "schema registry")
+            .is_some_and(|schemas| schemas.contains_key(name))
+    }
+}
+
+impl<Name, T> SchemaRegistry<Name, T>
+where
+    Name: Ord + Clone,
+    T: Identify,
+{
+    /// Returns the names of all the schemas in the registry, in order.
+    pub fn names(&self) -> Vec<Name> {
+        self.poisoning
+            .apply(self.schemas.read(), "schema registry")
+            .map(|schemas| schemas.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph, Source,
+        },
+        poison::PoisonPolicy,
+        schema::{registry::SchemaRegistry, transaction::Transaction, Error, Schema},
+    };
+
+    #[test]
+    fn schemas_should_be_isolated() {
+        let registry = SchemaRegistry::default()
+            .with_schema(
+                "foo",
+                Schema::from(Graph::default().with_node(fake_node!(1))),
+            )
+            .and_then(|registry| {
+                registry.with_schema(
+                    "bar",
+                    Schema::from(Graph::default().with_node(fake_node!(1))),
+                )
+            })
+            .expect("registering schemas should not fail");
+
+        registry
+            .get(&"foo")
+            .expect("registry should be readable")
+            .expect("foo schema should exist")
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(1)?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert!(
+            !registry
+                .get(&"foo")
+                .expect("registry should be readable")
+                .expect("foo schema should exist")
+                .read()
+                .expect("graph should be readable")
+                .contains(&1),
+            "node should be deleted from foo"
+        );
+
+        assert!(
+            registry
+                .get(&"bar")
+                .expect("registry should be readable")
+                .expect("bar schema should exist")
+                .read()
+                .expect("graph should be readable")
+                .contains(&1),
+            "node with the same id should be kept in bar"
+        );
+
+        assert_eq!(registry.names(), vec!["bar", "foo"]);
+
+        registry
+            .remove(&"foo")
+            .expect("registry should be writable");
+        assert!(
+            !registry.contains(&"foo"),
+            "removed schema should not exist"
+        );
+    }
+
+    #[test]
+    fn poisoned_registry_should_follow_policy() {
+        let registry = SchemaRegistry::default().with_poisoning(PoisonPolicy::Fail);
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = registry.schemas.write();
+            panic!("poisoning the registry");
+        }));
+
+        assert!(
+            matches!(
+                registry.insert(
+                    "foo",
+                    Schema::from(Graph::default().with_node(fake_node!(1)))
+                ),
+                Err(Error::Poisoned)
+            ),
+            "insert should fail as poisoned"
+        );
+
+        assert!(
+            matches!(registry.get(&"foo"), Err(Error::Poisoned)),
+            "get should fail as poisoned"
+        );
+
+        assert!(
+            matches!(registry.remove(&"foo"), Err(Error::Poisoned)),
+            "remove should fail as poisoned"
+        );
+    }
+}
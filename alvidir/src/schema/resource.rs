@@ -1,7 +1,7 @@
 //! Resources from the schema.
 
 use std::{
-    any::{Any, TypeId},
+    any::{self, Any, TypeId},
     collections::BTreeMap,
     marker::PhantomData,
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
@@ -10,14 +10,19 @@ use std::{
 use crate::{
     deref::{ReadOnly, ReadWrite, TryDeref, TryDerefMut, With},
     id::Identify,
+    poison::{PoisonPolicy, SharedPoisonPolicy},
 };
 
 use super::transaction::Context;
 
 /// Represents a set of arbitrary resources.
+///
+/// Poisoned resources are handled as told by the set's [`PoisonPolicy`]. If the policy refuses to
+/// access them, guards over them are empty, as if the resource did not exist.
 #[derive(Debug, Default)]
 pub struct ResourceSet {
     resources: BTreeMap<TypeId, Arc<RwLock<Box<dyn Any>>>>,
+    poisoning: SharedPoisonPolicy,
 }
 
 impl ResourceSet {
@@ -34,11 +39,24 @@ impl ResourceSet {
             .insert(type_id, Arc::new(RwLock::new(Box::new(resource))));
         self
     }
+
+    /// Sets how poisoned resources are handled.
+    ///
+    /// The policy also applies to any [`Res`] already taken from this set.
+    pub fn with_poisoning(self, poisoning: PoisonPolicy) -> Self {
+        self.poisoning.set(poisoning);
+        self
+    }
 }
 
 /// A resource that may, or may not, exist in the schema.
+///
+/// Resources taken from the context of a dry run are read-only: writing into them finds no
+/// resource.
 pub struct Res<T> {
     lock: Option<Arc<RwLock<Box<dyn Any>>>>,
+    poisoning: SharedPoisonPolicy,
+    read_only: bool,
     _type: PhantomData<T>,
 }
 
@@ -80,15 +98,12 @@ where
             return Default::default();
         };
 
-        match lock.read() {
-            Ok(guard) => ResReadGuard {
-                guard: Some(guard),
-                _type: PhantomData,
-            },
-            Err(err) => {
-                tracing::error!(error = err.to_string(), type_id = ?TypeId::of::<T>(), "accessing resource");
-                Default::default()
-            }
+        ResReadGuard {
+            guard: self
+                .poisoning
+                .get()
+                .apply(lock.read(), any::type_name::<T>()),
+            _type: PhantomData,
         }
     }
 }
@@ -140,15 +155,21 @@ where
             return Default::default();
         };
 
-        match lock.write() {
-            Ok(guard) => ResWriteGuard {
-                guard: Some(guard),
-                _type: PhantomData,
-            },
-            Err(err) => {
-                tracing::error!(error = err.to_string(), type_id = ?TypeId::of::<T>(), "accessing resource");
-                Default::default()
-            }
+        if self.read_only {
+            tracing::debug!(
+                resource = any::type_name::<T>(),
+                "refusing to write read-only resource"
+            );
+
+            return Default::default();
+        }
+
+        ResWriteGuard {
+            guard: self
+                .poisoning
+                .get()
+                .apply(lock.write(), any::type_name::<T>()),
+            _type: PhantomData,
         }
     }
 }
@@ -170,6 +191,8 @@ where
     fn from(set: &ResourceSet) -> Self {
         Self {
             lock: set.resources.get(&TypeId::of::<T>()).cloned(),
+            poisoning: set.poisoning.clone(),
+            read_only: false,
             _type: PhantomData,
         }
     }
@@ -181,16 +204,22 @@ where
     R: 'static,
 {
     fn from(ctx: &Context<T>) -> Self {
-        ctx.resources().into()
+        Self {
+            read_only: ctx.is_dry_run(),
+            ..ctx.resources().into()
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+
     use crate::{
         deref::{With, WithMut},
         graph::Graph,
         id::fixtures::IndentifyMock,
+        poison::PoisonPolicy,
         schema::{resource::Res, Schema},
     };
 
@@ -229,4 +258,70 @@ mod tests {
         })
         .expect("resource from the schema should exists");
     }
+
+    #[test]
+    fn poisoned_resource_should_follow_policy() {
+        struct Test {
+            name: &'static str,
+            policy: Option<PoisonPolicy>,
+            exists: bool,
+        }
+
+        struct Foo;
+
+        vec![
+            Test {
+                name: "default",
+                policy: None,
+                exists: true,
+            },
+            Test {
+                name: "fail",
+                policy: Some(PoisonPolicy::Fail),
+                exists: false,
+            },
+            Test {
+                name: "recover",
+                policy: Some(PoisonPolicy::Recover),
+                exists: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let mut schema = Schema::from(Graph::<IndentifyMock<usize>>::default());
+            if let Some(policy) = test.policy {
+                schema = schema.with_poisoning(policy);
+            }
+
+            let schema = schema.with_resource(Foo);
+            let res = Res::<Foo>::from(schema.resources());
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                res.with_mut(|_| panic!("poisoning the resource"));
+            }));
+
+            assert_eq!(res.exists(), test.exists, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn resource_should_follow_later_policies() {
+        struct Foo;
+
+        let schema = Schema::from(Graph::<IndentifyMock<usize>>::default()).with_resource(Foo);
+        let res = Res::<Foo>::from(schema.resources());
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            res.with_mut(|_| panic!("poisoning the resource"));
+        }));
+
+        assert!(
+            res.exists(),
+            "poisoned resource should be recovered by default"
+        );
+
+        let _schema = schema.with_poisoning(PoisonPolicy::Fail);
+        assert!(
+            !res.exists(),
+            "resource taken before setting the policy should follow it"
+        );
+    }
 }
@@ -1,14 +1,24 @@
 //! Transaction definition.
 
-use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::{
+    collections::BTreeSet,
+    ops::Deref,
+    sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
 use crate::{
     deref::{ReadOnly, ReadWrite, TryDeref, TryDerefMut},
     graph::{Graph, NodeProxy, Source},
     id::Identify,
+    poison::PoisonPolicy,
 };
 
-use super::{guard::SchemaWriteGuard, resource::ResourceSet, trigger::TriggerSet, Result, Schema};
+use super::{
+    guard::{SchemaReadGuard, SchemaWriteGuard},
+    resource::ResourceSet,
+    trigger::TriggerSet,
+    Error, Result, Schema,
+};
 
 /// Represents a set of operations that must be perfomed as a whole.
 pub trait Transaction: Sized {
@@ -21,14 +31,45 @@ pub trait Transaction: Sized {
         F: FnOnce(Context<'_, Self::Target>) -> Result<T>;
 }
 
+/// A closure receiving the changeset of a transaction.
+type Report<'a, T> = Box<dyn FnOnce(&Changeset<T>) + 'a>;
+
+/// The graph a transaction works on.
+enum GraphAccess<'a, T>
+where
+    T: Identify,
+{
+    /// The graph of the schema, locked for writing until the transaction ends.
+    Locked(SchemaWriteGuard<'a, T>),
+    /// The graph of the schema, locked for reading until the dry run ends.
+    Shared(SchemaReadGuard<'a, T>),
+}
+
+impl<T> Deref for GraphAccess<'_, T>
+where
+    T: Identify,
+{
+    type Target = Graph<T>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            GraphAccess::Locked(guard) => guard,
+            GraphAccess::Shared(guard) => guard,
+        }
+    }
+}
+
 /// Represents a set of operations that must be completed transactionally.
 pub struct Background<'a, T>
 where
     T: Identify,
 {
     schema: &'a Schema<T>,
-    guard: OnceLock<SchemaWriteGuard<'a, T>>,
+    guard: OnceLock<GraphAccess<'a, T>>,
     operations: Arc<RwLock<Vec<Operation<T>>>>,
+    /// Receives the changeset of a dry run instead of committing it.
+    dry_run: Option<Report<'a, T>>,
+    report: Option<Report<'a, T>>,
 }
 
 impl<'a, T> From<&'a Schema<T>> for Background<'a, T>
@@ -40,10 +81,44 @@ where
             schema,
             guard: Default::default(),
             operations: Default::default(),
+            dry_run: Default::default(),
+            report: Default::default(),
         }
     }
 }
 
+impl<'a, T> Background<'a, T>
+where
+    T: Identify,
+{
+    /// Hands the changeset of the transaction to the given closure once committed.
+    ///
+    /// The closure is called after subscribers have been notified, and never if the transaction
+    /// is aborted or it is a dry run.
+    pub fn with_report<F>(mut self, report: F) -> Self
+    where
+        F: FnOnce(&Changeset<T>) + 'a,
+    {
+        self.report = Some(Box::new(report));
+        self
+    }
+
+    /// Turns the transaction into a dry run, handing the changeset it would commit to the given
+    /// closure instead of applying it.
+    ///
+    /// Triggers are executed as usual, but resources are read-only to them: writing into a
+    /// resource finds none, so a dry run never changes the state of the schema. Subscribers are
+    /// never notified. A dry run reads the graph under a read lock, so it never blocks readers and
+    /// it is allowed in read-only schemas.
+    pub fn with_dry_run<F>(mut self, report: F) -> Self
+    where
+        F: FnOnce(&Changeset<T>) + 'a,
+    {
+        self.dry_run = Some(Box::new(report));
+        self
+    }
+}
+
 impl<T> Transaction for Background<'_, T>
 where
     T: Identify,
@@ -55,9 +130,13 @@ where
     where
         F: FnOnce(Context<'_, Self::Target>) -> Result<U>,
     {
-        f((&self).into()).inspect(|_| {
-            self.commit();
-        })
+        if self.schema.is_read_only() && self.dry_run.is_none() {
+            return Err(Error::ReadOnly);
+        }
+
+        let value = f((&self).try_into()?)?;
+        self.commit()?;
+        Ok(value)
     }
 }
 
@@ -66,26 +145,53 @@ where
     T: Identify,
     T::Id: Clone + Ord,
 {
-    fn commit(mut self) {
-        let Some(mut guard) = self.guard.take() else {
-            tracing::error!("committing uninitialized transaction");
-            return;
+    /// Applies the operations of the transaction into the schema.
+    ///
+    /// Fails with [`Error::Poisoned`] if the operations are poisoned and the schema's policy
+    /// refuses to access them, in which case nothing is applied.
+    fn commit(mut self) -> Result<()> {
+        let Some(access) = self.guard.take() else {
+            return Err(Error::custom("committing uninitialized transaction"));
         };
 
         let Some(ops) = Arc::into_inner(self.operations) else {
-            tracing::error!("commiting transaction with contexts yet in use");
-            return;
+            return Err(Error::custom(
+                "committing transaction with contexts yet in use",
+            ));
         };
 
-        let ops = match ops.into_inner() {
-            Ok(ops) => ops,
-            Err(err) => {
-                tracing::error!(error = err.to_string(), "committing poisoned transaction");
-                return;
+        let ops = self
+            .schema
+            .poisoning
+            .apply(ops.into_inner(), "transaction operations")
+            .ok_or(Error::Poisoned)?;
+
+        let mut changeset = Changeset { operations: ops };
+        if self.schema.is_compacting() {
+            changeset = changeset.compact();
+        }
+
+        let mut guard = match access {
+            GraphAccess::Locked(guard) => guard,
+            GraphAccess::Shared(_) => {
+                if let Some(report) = self.dry_run.take() {
+                    report(&changeset);
+                }
+
+                return Ok(());
             }
         };
 
-        ops.into_iter().for_each(|op| match op {
+        self.schema
+            .subscribers()
+            .iter()
+            .for_each(|subscriber| subscriber(&changeset, &guard));
+
+        if let Some(report) = self.report.take() {
+            report(&changeset);
+        }
+
+        changeset.operations.into_iter().for_each(|op| match op {
             Operation::Save(node) => {
                 guard.insert(node);
             }
@@ -93,6 +199,13 @@ where
                 guard.remove(&node_id);
             }
         });
+
+        self.schema
+            .observers()
+            .iter()
+            .for_each(|observer| observer(&guard));
+
+        Ok(())
     }
 }
 
@@ -127,9 +240,9 @@ where
     where
         F: FnOnce(Context<'_, Self::Target>) -> Result<U>,
     {
-        f((&self).into()).inspect(|_| {
-            self.commit();
-        })
+        let value = f((&self).into())?;
+        self.commit()?;
+        Ok(value)
     }
 }
 
@@ -137,41 +250,39 @@ impl<T> Foreground<'_, T>
 where
     T: Identify,
 {
-    fn commit(self) {
+    /// Moves the operations of the transaction into the parent context.
+    ///
+    /// Fails with [`Error::Poisoned`] if any of the operations are poisoned and the schema's
+    /// policy refuses to access them, in which case nothing is moved.
+    fn commit(self) -> Result<()> {
         let Some(ops) = Arc::into_inner(self.operations) else {
-            tracing::error!("commiting transaction with contexts yet in use");
-            return;
+            return Err(Error::custom(
+                "committing transaction with contexts yet in use",
+            ));
         };
 
-        let ops = match ops.into_inner() {
-            Ok(ops) => ops,
-            Err(err) => {
-                tracing::error!(error = err.to_string(), "committing poisoned transaction");
-                return;
-            }
-        };
+        let poisoning = self.context.schema.poisoning;
+        let ops = poisoning
+            .apply(ops.into_inner(), "transaction operations")
+            .ok_or(Error::Poisoned)?;
 
-        let mut upstream_ops = match self.context.operations.write() {
-            Ok(ops) => ops,
-            Err(err) => {
-                tracing::error!(
-                    error = err.to_string(),
-                    "committing transaction into poisoned context"
-                );
-                return;
-            }
-        };
+        poisoning
+            .apply(self.context.operations.write(), "transaction operations")
+            .ok_or(Error::Poisoned)?
+            .extend(ops);
 
-        upstream_ops.extend(ops);
+        Ok(())
     }
 }
 
 /// Represents an operation into the schema.
-enum Operation<T>
+pub enum Operation<T>
 where
     T: Identify,
 {
+    /// Inserts the node, overwriting any previous one with the same id.
     Save(T),
+    /// Removes the node with the given id.
     Delete(T::Id),
 }
 
@@ -189,15 +300,72 @@ where
     }
 }
 
+/// The set of operations committed by a transaction.
+pub struct Changeset<T>
+where
+    T: Identify,
+{
+    operations: Vec<Operation<T>>,
+}
+
+/// A closure notified with the changeset of every committed transaction, as well as the graph it
+/// is about to be applied to.
+pub type Subscriber<T> = Box<dyn Fn(&Changeset<T>, &Graph<T>)>;
+
+/// A closure notified with the graph every committed changeset has just been applied to.
+pub type Observer<T> = Box<dyn Fn(&Graph<T>)>;
+
+impl<T> Changeset<T>
+where
+    T: Identify,
+{
+    /// Returns an iterator over the operations in the order they are applied.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Operation<T>> {
+        self.operations.iter()
+    }
+
+    /// Returns the amount of operations in the changeset.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Returns true if, and only if, the changeset has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+impl<T> Changeset<T>
+where
+    T: Identify,
+    T::Id: Clone + Ord,
+{
+    /// Keeps only the last operation of each node, which is the only one having effect.
+    fn compact(self) -> Self {
+        let mut node_ids = BTreeSet::new();
+        let mut operations: Vec<_> = self
+            .operations
+            .into_iter()
+            .rev()
+            .filter(|op| node_ids.insert(op.id().clone()))
+            .collect();
+
+        operations.reverse();
+        Self { operations }
+    }
+}
+
 /// The node targeted by a context.
 pub struct Target<T> {
     lock: Option<Arc<RwLock<T>>>,
+    poisoning: PoisonPolicy,
 }
 
 impl<T> Default for Target<T> {
     fn default() -> Self {
         Self {
             lock: Default::default(),
+            poisoning: Default::default(),
         }
     }
 }
@@ -206,6 +374,7 @@ impl<T> Clone for Target<T> {
     fn clone(&self) -> Self {
         Self {
             lock: self.lock.clone(),
+            poisoning: self.poisoning,
         }
     }
 }
@@ -244,12 +413,8 @@ where
             return Default::default();
         };
 
-        match lock.read() {
-            Ok(guard) => TargetReadGuard { guard: Some(guard) },
-            Err(err) => {
-                tracing::error!(error = err.to_string(), "accessing transaction target");
-                Default::default()
-            }
+        TargetReadGuard {
+            guard: self.poisoning.apply(lock.read(), "transaction target"),
         }
     }
 }
@@ -294,12 +459,8 @@ where
             return Default::default();
         };
 
-        match lock.write() {
-            Ok(guard) => TargetWriteGuard { guard: Some(guard) },
-            Err(err) => {
-                tracing::error!(error = err.to_string(), "accessing transaction target");
-                Default::default()
-            }
+        TargetWriteGuard {
+            guard: self.poisoning.apply(lock.write(), "transaction target"),
         }
     }
 }
@@ -355,6 +516,8 @@ where
 {
     graph: &'a Graph<T>,
     schema: &'a Schema<T>,
+    /// Whether the context belongs to a dry run.
+    dry_run: bool,
     parent: Option<&'a Context<'a, T>>,
     operations: Arc<RwLock<Vec<Operation<T>>>>,
     target: Target<T>,
@@ -368,10 +531,10 @@ where
     type Node = T;
 
     fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Self::Node> {
-        let guard = match self.operations.read() {
-            Ok(ops) => ops,
-            Err(err) => err.into_inner(),
-        };
+        let guard = self
+            .schema
+            .poisoning
+            .apply(self.operations.read(), "transaction operations")?;
 
         match guard.iter().rev().find(|&op| op.id() == id) {
             Some(Operation::Save(node)) => Some(node.clone()),
@@ -384,9 +547,12 @@ where
     }
 
     fn contains(&self, id: &<Self::Node as Identify>::Id) -> bool {
-        let guard = match self.operations.read() {
-            Ok(ops) => ops,
-            Err(err) => err.into_inner(),
+        let Some(guard) = self
+            .schema
+            .poisoning
+            .apply(self.operations.read(), "transaction operations")
+        else {
+            return false;
         };
 
         match guard.iter().rev().find(|&op| op.id() == id) {
@@ -400,18 +566,35 @@ where
     }
 }
 
-impl<'a, T> From<&'a Background<'_, T>> for Context<'a, T>
+impl<'a, T> TryFrom<&'a Background<'_, T>> for Context<'a, T>
 where
     T: Identify,
 {
-    fn from(tx: &'a Background<'_, T>) -> Self {
-        Context {
+    type Error = Error;
+
+    /// Acquires the graph of the transaction's schema, if not yet, failing with
+    /// [`Error::Poisoned`] if the schema's policy refuses to access it.
+    fn try_from(tx: &'a Background<'_, T>) -> Result<Self> {
+        let graph = match tx.guard.get() {
+            Some(access) => access,
+            None => {
+                let access = match &tx.dry_run {
+                    Some(_) => GraphAccess::Shared(tx.schema.read()?),
+                    None => GraphAccess::Locked(tx.schema.write()?),
+                };
+
+                tx.guard.get_or_init(|| access)
+            }
+        };
+
+        Ok(Context {
             schema: tx.schema,
-            graph: tx.guard.get_or_init(|| tx.schema.write()),
+            graph,
+            dry_run: tx.dry_run.is_some(),
             operations: tx.operations.clone(),
             target: Default::default(),
             parent: Default::default(),
-        }
+        })
     }
 }
 
@@ -423,6 +606,7 @@ where
         Context {
             graph: tx.context.graph,
             schema: tx.context.schema,
+            dry_run: tx.context.dry_run,
             operations: tx.operations.clone(),
             target: Default::default(),
             parent: Some(tx.context),
@@ -441,34 +625,79 @@ where
     }
 }
 
+impl<T> Context<'_, T>
+where
+    T: Identify + Clone,
+    T::Id: Ord + Clone,
+{
+    /// Calls the given closure with the latest operation over each node touched by this context,
+    /// or any of its parents, and yet to be committed.
+    ///
+    /// Fails with [`Error::Poisoned`] if the operations of any context are poisoned and the
+    /// schema's policy refuses to access them.
+    pub fn for_each_change<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&Operation<T>) -> Result<()>,
+    {
+        self.visit_changes(&mut BTreeSet::new(), &mut f)
+    }
+
+    fn visit_changes(
+        &self,
+        visited: &mut BTreeSet<T::Id>,
+        f: &mut dyn FnMut(&Operation<T>) -> Result<()>,
+    ) -> Result<()> {
+        self.schema
+            .poisoning
+            .apply(self.operations.read(), "transaction operations")
+            .ok_or(Error::Poisoned)?
+            .iter()
+            .rev()
+            .filter(|op| visited.insert(op.id().clone()))
+            .try_for_each(&mut *f)?;
+
+        match self.parent {
+            Some(parent) => parent.visit_changes(visited, f),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<T> Context<'_, T>
 where
     T: Identify,
 {
     /// Assigns a target to this context.
     pub fn with_target(mut self, target: T) -> Self {
+        self.target.poisoning = self.schema.poisoning;
         self.target.set(target);
         self
     }
 
     /// Registers the save operation as part of the transaction.
-    pub fn save(&self, node: T) {
-        let mut guard = match self.operations.write() {
-            Ok(ops) => ops,
-            Err(err) => err.into_inner(),
-        };
-
-        guard.push(Operation::Save(node));
+    ///
+    /// Fails with [`Error::Poisoned`] if the operations of the context are poisoned and the
+    /// schema's policy refuses to access them.
+    pub fn save(&self, node: T) -> Result<()> {
+        self.push(Operation::Save(node))
     }
 
     /// Registers the delete operation as part of the transaction.
-    pub fn delete(&self, node_id: T::Id) {
-        let mut guard = match self.operations.write() {
-            Ok(ops) => ops,
-            Err(err) => err.into_inner(),
-        };
+    ///
+    /// Fails with [`Error::Poisoned`] if the operations of the context are poisoned and the
+    /// schema's policy refuses to access them.
+    pub fn delete(&self, node_id: T::Id) -> Result<()> {
+        self.push(Operation::Delete(node_id))
+    }
+
+    fn push(&self, op: Operation<T>) -> Result<()> {
+        self.schema
+            .poisoning
+            .apply(self.operations.write(), "transaction operations")
+            .ok_or(Error::Poisoned)?
+            .push(op);
 
-        guard.push(Operation::Delete(node_id));
+        Ok(())
     }
 
     /// Returns a reference to the underlying schema's [`ResourceSet`].
@@ -476,6 +705,11 @@ where
         self.schema.resources()
     }
 
+    /// Returns true if, and only if, the context belongs to a dry run.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
     /// Returns a reference to the underlying schema's [`TriggerSet`].
     pub fn triggers(&self) -> &TriggerSet<T> {
         self.schema.triggers()
@@ -512,6 +746,32 @@ where
     }
 }
 
+impl<'a, T> Ctx<'a, T>
+where
+    T: Identify + Clone,
+    T::Id: Ord,
+{
+    /// Returns the [`NodeProxy`] for the given id.
+    pub fn node(&self, node_id: T::Id) -> NodeProxy<'a, Context<'a, T>> {
+        self.context.node(node_id)
+    }
+}
+
+impl<T> Ctx<'_, T>
+where
+    T: Identify + Clone,
+    T::Id: Ord + Clone,
+{
+    /// Calls the given closure with the latest operation over each node touched by the context
+    /// and yet to be committed.
+    pub fn for_each_change<F>(&self, f: F) -> Result<()>
+    where
+        F: FnMut(&Operation<T>) -> Result<()>,
+    {
+        self.context.for_each_change(f)
+    }
+}
+
 impl<'a, T> From<&'a Context<'a, T>> for Ctx<'a, T>
 where
     T: Identify,
@@ -523,12 +783,28 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
     use crate::{
+        deref::{With, WithMut},
         graph::{
             fixtures::{fake_node, FakeNode},
             Graph, Source,
         },
-        schema::{transaction::Context, Error, Result, Schema},
+        id::Identify,
+        poison::PoisonPolicy,
+        schema::{
+            ops::save::{BeforeSave, Save},
+            resource::Res,
+            transaction::{Context, Ctx, Operation},
+            Error, Result, Schema,
+        },
     };
 
     use super::Transaction;
@@ -539,13 +815,13 @@ mod tests {
 
         let tx = schema.transaction();
         tx.with(|ctx| {
-            ctx.delete(1);
+            ctx.delete(1)?;
             assert!(
                 !ctx.contains(&1),
                 "deletion should be registered into the context"
             );
 
-            ctx.save(fake_node!(2));
+            ctx.save(fake_node!(2))?;
             assert!(
                 ctx.contains(&2),
                 "save should be registered into the context"
@@ -562,20 +838,26 @@ mod tests {
         schema
             .transaction()
             .with(|ctx| {
-                ctx.delete(1);
-                ctx.save(fake_node!(2));
+                ctx.delete(1)?;
+                ctx.save(fake_node!(2))?;
 
                 Result::<()>::Err(Error::custom("failed transaction"))
             })
             .expect_err("transaction error should be propagated");
 
         assert!(
-            schema.read().contains(&1),
+            schema
+                .read()
+                .expect("graph should be readable")
+                .contains(&1),
             "uncommitted transaction should not apply changes"
         );
 
         assert!(
-            !schema.read().contains(&2),
+            !schema
+                .read()
+                .expect("graph should be readable")
+                .contains(&2),
             "uncommitted transaction should not apply changes"
         );
     }
@@ -586,38 +868,401 @@ mod tests {
         let tx = schema.transaction();
 
         tx.with(|ctx| {
-            ctx.delete(1);
-            ctx.save(fake_node!(2));
+            ctx.delete(1)?;
+            ctx.save(fake_node!(2))?;
 
             Ok(())
         })
         .expect("transaction should not fail");
 
         assert!(
-            !schema.read().contains(&1),
+            !schema
+                .read()
+                .expect("graph should be readable")
+                .contains(&1),
             "committed transaction should apply changes"
         );
 
         assert!(
-            schema.read().contains(&2),
+            schema
+                .read()
+                .expect("graph should be readable")
+                .contains(&2),
             "committed transaction should apply changes"
         );
     }
 
+    #[test]
+    fn commit_should_compact_operations() {
+        struct Test<'a> {
+            name: &'a str,
+            compaction: bool,
+            changes: usize,
+        }
+
+        vec![
+            Test {
+                name: "compacting schema",
+                compaction: true,
+                changes: 2,
+            },
+            Test {
+                name: "non-compacting schema",
+                compaction: false,
+                changes: 4,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let changes = Arc::new(AtomicUsize::default());
+            let schema: Schema<_> = Graph::default().with_node(fake_node!(1)).into();
+            let schema = schema.with_compaction(test.compaction).with_subscriber({
+                let changes = changes.clone();
+                move |changeset, _| changes.store(changeset.len(), Ordering::Relaxed)
+            });
+
+            schema
+                .transaction()
+                .with(|ctx| {
+                    ctx.save(fake_node!(2))?;
+                    ctx.delete(1)?;
+                    ctx.delete(2)?;
+                    ctx.save(fake_node!(1))?;
+                    Ok(())
+                })
+                .expect("transaction should not fail");
+
+            assert_eq!(
+                changes.load(Ordering::Relaxed),
+                test.changes,
+                "{}",
+                test.name
+            );
+            assert!(
+                schema
+                    .read()
+                    .expect("graph should be readable")
+                    .contains(&1),
+                "{}",
+                test.name
+            );
+            assert!(
+                !schema
+                    .read()
+                    .expect("graph should be readable")
+                    .contains(&2),
+                "{}",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn dry_run_should_report_without_committing() {
+        let notified = Arc::new(AtomicUsize::default());
+        let schema: Schema<_> = Graph::default().with_node(fake_node!(1)).into();
+        let schema = schema.with_read_only(true).with_subscriber({
+            let notified = notified.clone();
+            move |_, _| {
+                notified.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        // A dry run locking the graph for writing would never complete while it is being read.
+        let graph = schema.read().expect("graph should be readable");
+
+        let mut reported = 0;
+        schema
+            .transaction()
+            .with_dry_run(|changeset| reported = changeset.len())
+            .with(|ctx| {
+                ctx.delete(1)?;
+                ctx.save(fake_node!(2))?;
+                Ok(())
+            })
+            .expect("dry run should be allowed in read-only schemas");
+
+        drop(graph);
+
+        assert_eq!(reported, 2, "dry run should report the changeset");
+        assert_eq!(
+            notified.load(Ordering::Relaxed),
+            0,
+            "dry run should not notify subscribers"
+        );
+
+        assert!(
+            schema
+                .read()
+                .expect("graph should be readable")
+                .contains(&1)
+                && !schema
+                    .read()
+                    .expect("graph should be readable")
+                    .contains(&2),
+            "dry run should not apply changes"
+        );
+    }
+
+    #[test]
+    fn dry_run_should_not_write_resources() {
+        struct Saves(usize);
+
+        fn count_saves(_: Ctx<FakeNode<'static, usize>>, saves: Res<Saves>) -> Result<()> {
+            saves.with_mut(|saves| saves.0 += 1);
+            Ok(())
+        }
+
+        let schema = Schema::from(Graph::default().with_node(fake_node!(1)))
+            .with_resource(Saves(0))
+            .with_trigger(BeforeSave, count_saves);
+
+        let saves = || Res::<Saves>::from(schema.resources()).with(|saves| saves.0);
+
+        Save::new(fake_node!(2))
+            .execute(schema.transaction().with_dry_run(|_| {}))
+            .expect("dry run should not fail");
+
+        assert_eq!(saves(), Some(0), "dry run should not write resources");
+
+        Save::new(fake_node!(2))
+            .execute(schema.transaction())
+            .expect("transaction should not fail");
+
+        assert_eq!(saves(), Some(1), "transaction should write resources");
+    }
+
+    #[test]
+    fn report_should_receive_committed_changesets() {
+        let schema: Schema<_> = Graph::default().with_node(fake_node!(1)).into();
+
+        let mut reported = None;
+        let _ = schema
+            .transaction()
+            .with_report(|changeset| reported = Some(changeset.len()))
+            .with(|ctx| {
+                ctx.delete(1)?;
+                Err::<(), _>(Error::Noop)
+            });
+
+        assert_eq!(reported, None, "aborted transaction should not be reported");
+
+        let mut reported = None;
+        schema
+            .transaction()
+            .with_report(|changeset| reported = Some(changeset.len()))
+            .with(|ctx| {
+                ctx.delete(1)?;
+                ctx.save(fake_node!(2))?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            reported,
+            Some(2),
+            "committed transaction should report its changeset"
+        );
+    }
+
+    #[test]
+    fn observers_should_see_applied_changes() {
+        let observed = Arc::new(AtomicUsize::default());
+        let schema: Schema<_> = Graph::default().with_node(fake_node!(1)).into();
+        let schema = schema.with_observer({
+            let observed = observed.clone();
+            move |graph| {
+                if graph.contains(&2) && !graph.contains(&1) {
+                    observed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(1)?;
+                ctx.save(fake_node!(2))?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            observed.load(Ordering::Relaxed),
+            1,
+            "observers should be notified with the graph already written"
+        );
+    }
+
+    #[test]
+    fn read_only_schema_should_reject_transactions() {
+        let schema: Schema<_> = Graph::default().with_node(fake_node!(1)).into();
+        let schema = schema.with_read_only(true);
+
+        let result = schema.transaction().with(|ctx| {
+            ctx.delete(1)?;
+            Ok(())
+        });
+
+        assert!(
+            matches!(result, Err(Error::ReadOnly)),
+            "read-only schema should reject transactions"
+        );
+
+        assert!(
+            schema
+                .read()
+                .expect("graph should be readable")
+                .contains(&1),
+            "rejected transaction should not apply changes"
+        );
+    }
+
+    #[test]
+    fn poisoned_schema_should_follow_policy() {
+        struct Test {
+            name: &'static str,
+            policy: PoisonPolicy,
+            committed: bool,
+        }
+
+        vec![
+            Test {
+                name: "fail",
+                policy: PoisonPolicy::Fail,
+                committed: false,
+            },
+            Test {
+                name: "recover",
+                policy: PoisonPolicy::Recover,
+                committed: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let schema =
+                Schema::from(Graph::default().with_node(fake_node!(1))).with_poisoning(test.policy);
+
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                let _guard = schema.write();
+                panic!("poisoning the schema");
+            }));
+
+            let result = schema.transaction().with(|ctx| {
+                ctx.save(fake_node!(2))?;
+                Ok(())
+            });
+
+            assert_eq!(result.is_ok(), test.committed, "{}", test.name);
+            assert_eq!(
+                schema.read().is_ok(),
+                test.committed,
+                "{}: reading the graph should follow the policy",
+                test.name
+            );
+
+            if !test.committed {
+                assert!(
+                    matches!(result, Err(Error::Poisoned)),
+                    "{}: transaction should be rejected as poisoned",
+                    test.name
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn poisoned_operations_should_follow_policy() {
+        struct Test {
+            name: &'static str,
+            policy: PoisonPolicy,
+            /// Whether the operations are poisoned before the last save or right before commit.
+            before_save: bool,
+            committed: bool,
+        }
+
+        vec![
+            Test {
+                name: "fail on save",
+                policy: PoisonPolicy::Fail,
+                before_save: true,
+                committed: false,
+            },
+            Test {
+                name: "fail on commit",
+                policy: PoisonPolicy::Fail,
+                before_save: false,
+                committed: false,
+            },
+            Test {
+                name: "recover",
+                policy: PoisonPolicy::Recover,
+                before_save: true,
+                committed: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let schema =
+                Schema::from(Graph::default().with_node(fake_node!(1))).with_poisoning(test.policy);
+
+            let poison = |ctx: &Context<FakeNode<usize>>| {
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                    let _guard = ctx.operations.write();
+                    panic!("poisoning the operations");
+                }));
+            };
+
+            let result = schema.transaction().with(|ctx| {
+                ctx.delete(1)?;
+                if test.before_save {
+                    poison(&ctx);
+                }
+
+                ctx.save(fake_node!(2))?;
+                if !test.before_save {
+                    poison(&ctx);
+                }
+
+                Ok(())
+            });
+
+            let graph = schema.read().expect("graph should be readable");
+            assert_eq!(
+                !graph.contains(&1) && graph.contains(&2),
+                test.committed,
+                "{}: changes should be applied only if committed",
+                test.name
+            );
+
+            if test.committed {
+                assert!(result.is_ok(), "{}: got = {result:?}", test.name);
+            } else {
+                assert!(
+                    matches!(result, Err(Error::Poisoned)),
+                    "{}: transaction should be rejected as poisoned, got = {result:?}",
+                    test.name
+                );
+            }
+        });
+    }
+
     #[test]
     fn subtransactions_should_be_independent() {
         let schema: Schema<_> = Graph::default().with_node(fake_node!(1)).into();
 
         let tx_1 = schema.transaction();
-        let ctx_1 = Context::from(&tx_1);
+        let ctx_1 = Context::try_from(&tx_1).expect("context should be created");
 
         let tx_2 = ctx_1.transaction();
         let ctx_2 = Context::from(&tx_2);
-        ctx_2.delete(1);
+        ctx_2.delete(1).expect("delete should be registered");
 
         let tx_3 = ctx_1.transaction();
         let ctx_3 = Context::from(&tx_3);
-        ctx_3.save(fake_node!(2));
+        ctx_3
+            .save(fake_node!(2))
+            .expect("save should be registered");
 
         assert!(
             ctx_1.contains(&1),
@@ -647,6 +1292,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn context_changes_should_include_parent_changes() {
+        let schema: Schema<_> = Graph::default()
+            .with_node(fake_node!(1))
+            .with_node(fake_node!(2))
+            .into();
+
+        let tx_1 = schema.transaction();
+        let ctx_1 = Context::try_from(&tx_1).expect("context should be created");
+        ctx_1.delete(1).expect("delete should be registered");
+        ctx_1
+            .save(fake_node!(3))
+            .expect("save should be registered");
+
+        let tx_2 = ctx_1.transaction();
+        let ctx_2 = Context::from(&tx_2);
+        ctx_2
+            .save(fake_node!(1))
+            .expect("save should be registered");
+
+        let changes = |ctx: &Context<FakeNode<usize>>| {
+            let mut changes = Vec::new();
+            ctx.for_each_change(|op| {
+                changes.push(match op {
+                    Operation::Save(node) => (*node.id(), true),
+                    Operation::Delete(node_id) => (*node_id, false),
+                });
+                Ok(())
+            })
+            .expect("changes should be accessible");
+
+            changes.sort();
+            changes
+        };
+
+        assert_eq!(
+            changes(&ctx_1),
+            vec![(1, false), (3, true)],
+            "changes of subtransactions should not be listed"
+        );
+
+        assert_eq!(
+            changes(&ctx_2),
+            vec![(1, true), (3, true)],
+            "only the latest change of each node should be listed"
+        );
+    }
+
     #[test]
     fn committed_subtransaction_should_apply_on_parent_context() {
         let schema: Schema<_> = Graph::default().with_node(fake_node!(1)).into();
@@ -657,7 +1350,7 @@ mod tests {
                 ctx_1
                     .transaction()
                     .with(|ctx_2| {
-                        ctx_2.delete(1);
+                        ctx_2.delete(1)?;
                         Ok(())
                     })
                     .expect("transaction should not fail");
@@ -679,7 +1372,7 @@ mod tests {
         schema
             .transaction()
             .with(|ctx_1| {
-                ctx_1.save(fake_node!(1));
+                ctx_1.save(fake_node!(1))?;
                 ctx_1
                     .transaction()
                     .with(|ctx_2| {
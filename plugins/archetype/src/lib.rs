@@ -0,0 +1,43 @@
+//! A typing layer for the nodes and edges of a schema.
+
+mod plugin;
+pub use plugin::ArchetypePlugin;
+
+use std::collections::BTreeSet;
+
+/// The set of edges allowed between archetypes.
+#[derive(Debug)]
+pub struct ArchetypeRules<A> {
+    /// All the allowed pairs of (source, destination) archetypes.
+    edges: BTreeSet<(A, A)>,
+}
+
+impl<A> Default for ArchetypeRules<A> {
+    fn default() -> Self {
+        Self {
+            edges: Default::default(),
+        }
+    }
+}
+
+impl<A> ArchetypeRules<A>
+where
+    A: Ord,
+{
+    /// Allows edges from nodes of the `from` archetype to nodes of the `to` one.
+    pub fn with_edge(mut self, from: A, to: A) -> Self {
+        self.edges.insert((from, to));
+        self
+    }
+}
+
+impl<A> ArchetypeRules<A>
+where
+    A: Ord + Clone,
+{
+    /// Returns true if, and only if, an edge from the `from` archetype to the `to` one is
+    /// allowed.
+    pub fn allows(&self, from: &A, to: &A) -> bool {
+        self.edges.contains(&(from.clone(), to.clone()))
+    }
+}
@@ -0,0 +1,477 @@
+//! The plugin implementation for [`ArchetypeRules`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    marker::PhantomData,
+};
+
+use alvidir::{
+    graph::Graph,
+    prelude::*,
+    property::Extract,
+    schema::transaction::{Changeset, Operation},
+};
+
+use crate::ArchetypeRules;
+
+/// The committed edges of type Edge, indexed by their destination.
+struct Referrers<Id, Edge> {
+    /// The nodes having an edge to each node.
+    incoming: BTreeMap<Id, BTreeSet<Id>>,
+    /// The destinations of the edges of each node.
+    outgoing: BTreeMap<Id, Vec<Id>>,
+    edge: PhantomData<Edge>,
+}
+
+impl<Id, Edge> Default for Referrers<Id, Edge> {
+    fn default() -> Self {
+        Self {
+            incoming: Default::default(),
+            outgoing: Default::default(),
+            edge: PhantomData,
+        }
+    }
+}
+
+impl<Id, Edge> Referrers<Id, Edge>
+where
+    Id: Ord + Clone,
+{
+    /// Indexes the edges of the given node, replacing the old ones.
+    fn insert<T>(&mut self, node: &T)
+    where
+        T: Identify<Id = Id>,
+        Edge: Property<T> + Identify<Id = Id>,
+    {
+        self.remove(node.id());
+
+        let destinations: Vec<_> = Edge::all(node)
+            .iter()
+            .map(|edge| edge.id().clone())
+            .collect();
+
+        destinations.iter().for_each(|destination| {
+            self.incoming
+                .entry(destination.clone())
+                .or_default()
+                .insert(node.id().clone());
+        });
+
+        self.outgoing.insert(node.id().clone(), destinations);
+    }
+
+    /// Removes the edges of the node with the given id.
+    fn remove(&mut self, node_id: &Id) {
+        let Some(destinations) = self.outgoing.remove(node_id) else {
+            return;
+        };
+
+        destinations.iter().for_each(|destination| {
+            if let Some(referrers) = self.incoming.get_mut(destination) {
+                referrers.remove(node_id);
+                if referrers.is_empty() {
+                    self.incoming.remove(destination);
+                }
+            }
+        });
+    }
+}
+
+/// Implements the [`Plugin`] trait for an arbitrary extractor of archetypes from a source of type
+/// T, validating that every edge of type Edge connects allowed archetypes.
+///
+/// Saving a node validates both its own edges and the ones other nodes have towards it, so typing
+/// a virtual node or changing the archetype of an existing one cannot leave disallowed edges
+/// behind. Committed edges are indexed by their destination, so finding the nodes pointing to the
+/// saved one never walks the whole graph.
+///
+/// Nodes declaring no archetype, as well as virtual nodes, are considered untyped and therefore
+/// any edge from or to them is allowed. When a node declares more than one archetype, an edge is
+/// allowed as long as any of the combinations is.
+pub struct ArchetypePlugin<T, Edge, Extractor>
+where
+    Extractor: Extract<T>,
+{
+    extractor: Extractor,
+    rules: ArchetypeRules<Extractor::Target>,
+    node: PhantomData<T>,
+    edge: PhantomData<Edge>,
+}
+
+impl<T, Edge, Extractor> ArchetypePlugin<T, Edge, Extractor>
+where
+    Extractor: Extract<T>,
+{
+    /// Returns a new plugin enforcing the given rules.
+    pub fn new(extractor: Extractor, rules: ArchetypeRules<Extractor::Target>) -> Self {
+        Self {
+            extractor,
+            rules,
+            node: PhantomData,
+            edge: PhantomData,
+        }
+    }
+}
+
+impl<T, Edge, Extractor> ArchetypePlugin<T, Edge, Extractor>
+where
+    T: 'static + Identify + Clone,
+    T::Id: Ord + Clone + Debug,
+    Edge: 'static + Property<T> + Identify<Id = T::Id>,
+    Extractor: 'static + Extract<T>,
+    Extractor::Target: 'static + Ord + Clone + Debug,
+{
+    fn on_save(
+        ctx: Ctx<T>,
+        target: Target<T>,
+        rules: Res<ArchetypeRules<Extractor::Target>>,
+        extractor: Res<Extractor>,
+        referrers: Res<Referrers<T::Id, Edge>>,
+    ) -> Result<()> {
+        let Some(target) = target.with(T::clone) else {
+            return Ok(());
+        };
+
+        (rules, extractor, referrers)
+            .with(|(rules, extractor, referrers)| {
+                let archetypes = extractor.all(&target);
+                if archetypes.is_empty() {
+                    return Ok(());
+                }
+
+                Edge::all(&target).into_iter().try_for_each(|edge| {
+                    let successors = if edge.id() == target.id() {
+                        archetypes.clone()
+                    } else {
+                        ctx.node(edge.id().clone())
+                            .try_deref()
+                            .map(|node| extractor.all(node))
+                            .unwrap_or_default()
+                    };
+
+                    check_edge(rules, (target.id(), &archetypes), (edge.id(), &successors))
+                })?;
+
+                // Edges pointing to the target may have been allowed while it was virtual,
+                // untyped, or had other archetypes.
+                let mut committed = referrers
+                    .incoming
+                    .get(target.id())
+                    .cloned()
+                    .unwrap_or_default();
+
+                ctx.for_each_change(|op| {
+                    committed.remove(op.id());
+
+                    let Operation::Save(node) = op else {
+                        return Ok(());
+                    };
+
+                    if node.id() == target.id()
+                        || !Edge::all(node).iter().any(|edge| edge.id() == target.id())
+                    {
+                        return Ok(());
+                    }
+
+                    check_edge(
+                        rules,
+                        (node.id(), &extractor.all(node)),
+                        (target.id(), &archetypes),
+                    )
+                })?;
+
+                committed
+                    .into_iter()
+                    .filter(|node_id| node_id != target.id())
+                    .try_for_each(|node_id| {
+                        let Some(node) = ctx.node(node_id.clone()).try_deref().cloned() else {
+                            return Ok(());
+                        };
+
+                        check_edge(
+                            rules,
+                            (&node_id, &extractor.all(&node)),
+                            (target.id(), &archetypes),
+                        )
+                    })
+            })
+            .unwrap_or_else(|| {
+                // Resources refused by the poison policy must never let a node through.
+                Err(Error::custom("archetype rules are not accessible"))
+            })
+    }
+}
+
+/// Fails if none of the archetypes of the source node allows an edge to any of the ones of the
+/// destination node. Untyped nodes allow any edge.
+fn check_edge<Id, A>(rules: &ArchetypeRules<A>, from: (&Id, &[A]), to: (&Id, &[A])) -> Result<()>
+where
+    Id: Debug,
+    A: Ord + Clone + Debug,
+{
+    let (from_id, from) = from;
+    let (to_id, to) = to;
+
+    let allowed = from.is_empty()
+        || to.is_empty()
+        || from
+            .iter()
+            .any(|from| to.iter().any(|to| rules.allows(from, to)));
+
+    if !allowed {
+        return Err(Error::rejected(format!(
+            "edge from {from:?} {from_id:?} to {to:?} {to_id:?} is not allowed"
+        )));
+    }
+
+    Ok(())
+}
+
+impl<T, Edge, Extractor> Plugin<T> for ArchetypePlugin<T, Edge, Extractor>
+where
+    T: 'static + Identify + Clone,
+    T::Id: Ord + Clone + Debug,
+    Edge: 'static + Property<T> + Identify<Id = T::Id>,
+    Extractor: 'static + Extract<T>,
+    Extractor::Target: 'static + Ord + Clone + Debug,
+{
+    fn install(self, schema: Schema<T>) -> Schema<T>
+    where
+        T: Identify,
+    {
+        let mut index = Referrers::<T::Id, Edge>::default();
+        // A graph refusing to be read has already been reported by its poison policy.
+        if let Ok(graph) = schema.read() {
+            graph.into_iter().for_each(|node| index.insert(node));
+        }
+
+        let schema = schema.with_resource(index);
+        let index = Res::<Referrers<T::Id, Edge>>::from(schema.resources());
+
+        schema
+            .with_resource(self.extractor)
+            .with_resource(self.rules)
+            .with_trigger(BeforeSave, Self::on_save)
+            .with_subscriber(move |changeset: &Changeset<T>, _: &Graph<T>| {
+                index.with_mut(|index| {
+                    changeset.iter().for_each(|op| match op {
+                        Operation::Save(node) => index.insert(node),
+                        Operation::Delete(node_id) => index.remove(node_id),
+                    })
+                });
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alvidir::{
+        graph::Graph,
+        prelude::*,
+        property::Extract,
+        schema::{ops::save::Save, Schema},
+    };
+
+    use crate::{ArchetypePlugin, ArchetypeRules};
+
+    #[derive(Debug, Clone)]
+    struct Node {
+        id: usize,
+        archetype: Option<&'static str>,
+        edges: Vec<usize>,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    struct Edge(usize);
+
+    impl Identify for Edge {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    impl Property<Node> for Edge {
+        fn all(source: &Node) -> Vec<Self> {
+            source.edges.iter().copied().map(Edge).collect()
+        }
+    }
+
+    struct ArchetypeExtractor;
+
+    impl Extract<Node> for ArchetypeExtractor {
+        type Target = &'static str;
+
+        fn all(&self, source: &Node) -> Vec<Self::Target> {
+            source.archetype.into_iter().collect()
+        }
+    }
+
+    #[test]
+    fn edges_must_connect_allowed_archetypes() {
+        struct Test<'a> {
+            name: &'a str,
+            node: Node,
+            success: bool,
+        }
+
+        vec![
+            Test {
+                name: "allowed edge",
+                node: Node {
+                    id: 3,
+                    archetype: Some("person"),
+                    edges: vec![1],
+                },
+                success: true,
+            },
+            Test {
+                name: "disallowed edge",
+                node: Node {
+                    id: 3,
+                    archetype: Some("person"),
+                    edges: vec![2],
+                },
+                success: false,
+            },
+            Test {
+                name: "untyped source",
+                node: Node {
+                    id: 3,
+                    archetype: None,
+                    edges: vec![2],
+                },
+                success: true,
+            },
+            Test {
+                name: "virtual destination",
+                node: Node {
+                    id: 3,
+                    archetype: Some("person"),
+                    edges: vec![4],
+                },
+                success: true,
+            },
+            Test {
+                name: "typing a virtual destination with an allowed archetype",
+                node: Node {
+                    id: 6,
+                    archetype: Some("place"),
+                    edges: Vec::default(),
+                },
+                success: true,
+            },
+            Test {
+                name: "typing a virtual destination with a disallowed archetype",
+                node: Node {
+                    id: 6,
+                    archetype: Some("person"),
+                    edges: Vec::default(),
+                },
+                success: false,
+            },
+            Test {
+                name: "changing the archetype of a destination",
+                node: Node {
+                    id: 1,
+                    archetype: Some("person"),
+                    edges: Vec::default(),
+                },
+                success: false,
+            },
+            Test {
+                name: "untyping a destination",
+                node: Node {
+                    id: 1,
+                    archetype: None,
+                    edges: Vec::default(),
+                },
+                success: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let schema = Schema::from(Graph::from_iter(vec![
+                Node {
+                    id: 1,
+                    archetype: Some("place"),
+                    edges: Vec::default(),
+                },
+                Node {
+                    id: 2,
+                    archetype: Some("person"),
+                    edges: Vec::default(),
+                },
+                Node {
+                    id: 5,
+                    archetype: Some("person"),
+                    edges: vec![1, 6],
+                },
+            ]))
+            .install(ArchetypePlugin::<_, Edge, _>::new(
+                ArchetypeExtractor,
+                ArchetypeRules::default().with_edge("person", "place"),
+            ));
+
+            let result = Save::new(test.node).execute(schema.transaction());
+            assert_eq!(result.is_ok(), test.success, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn incoming_edges_should_follow_changes() {
+        let person = |id, edges| Node {
+            id,
+            archetype: Some("person"),
+            edges,
+        };
+
+        let schema = Schema::from(Graph::from_iter(vec![Node {
+            id: 1,
+            archetype: Some("place"),
+            edges: Vec::default(),
+        }]))
+        .install(ArchetypePlugin::<_, Edge, _>::new(
+            ArchetypeExtractor,
+            ArchetypeRules::default().with_edge("person", "place"),
+        ));
+
+        Save::new(person(2, vec![3]))
+            .execute(schema.transaction())
+            .expect("edge to a virtual node should be allowed");
+
+        assert!(
+            Save::new(person(3, Vec::default()))
+                .execute(schema.transaction())
+                .is_err(),
+            "typing the destination of a committed edge should be checked"
+        );
+
+        let result = schema.transaction().with(|ctx| {
+            Save::new(person(4, vec![5])).execute(ctx.transaction())?;
+            Save::new(person(5, Vec::default())).execute(ctx.transaction())
+        });
+
+        assert!(
+            result.is_err(),
+            "typing the destination of an uncommitted edge should be checked"
+        );
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                Save::new(person(2, Vec::default())).execute(ctx.transaction())?;
+                Save::new(person(3, Vec::default())).execute(ctx.transaction())
+            })
+            .expect("edges removed by the transaction should not be checked");
+    }
+}
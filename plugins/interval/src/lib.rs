@@ -3,7 +3,12 @@
 #[cfg(feature = "date")]
 pub mod date;
 mod node;
+mod open;
+pub use open::{Limit, OpenInterval};
+#[cfg(feature = "date")]
+pub mod period;
 mod plugin;
+pub use plugin::{IntervalIndex, IntervalPlugin, TieBreak};
 mod tree;
 pub use tree::IntervalSearchTree;
 
@@ -30,7 +35,10 @@ trait IntervalExt: Interval {
     }
 
     /// Returns true if, and only if, self intersects other.
-    fn intersects(&self, other: &Self) -> bool {
+    fn intersects<Other>(&self, other: &Other) -> bool
+    where
+        Other: Interval<Bound = Self::Bound>,
+    {
         self.contains(other.lo())
             || self.contains(other.hi())
             || other.contains(self.lo())
@@ -49,7 +57,7 @@ pub mod fixtures {
     use super::{Bound, Interval};
 
     /// A mock implementation for the [`Interval`] trait.
-    #[derive(Default, Clone, PartialOrd, Ord)]
+    #[derive(Default, Clone)]
     pub struct IntervalMock<Bound> {
         lo_fn: Option<fn() -> Bound>,
         hi_fn: Option<fn() -> Bound>,
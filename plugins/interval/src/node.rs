@@ -1,8 +1,20 @@
 //! The node from an interval search tree.
-#![allow(dead_code)]
+
+use std::cmp::Ordering;
 
 use super::{Interval, IntervalExt};
 
+/// Orders two intervals sharing the same lower bound.
+pub type TieBreakFn<Intv> = fn(&Intv, &Intv) -> Ordering;
+
+/// Orders the given intervals by their lower bound, breaking ties with the given function.
+fn order<Intv>(a: &Intv, b: &Intv, tie_break: TieBreakFn<Intv>) -> Ordering
+where
+    Intv: Interval,
+{
+    a.lo().cmp(&b.lo()).then_with(|| tie_break(a, b))
+}
+
 /// A node in an interval search tree.
 #[derive(Debug, Clone, PartialEq)]
 pub struct IntervalSearchTreeNode<Intv>
@@ -24,75 +36,35 @@ where
     }
 }
 
-impl<Intv> IntervalSearchTreeNode<Intv>
-where
-    Intv: Interval,
-    Intv::Bound: Clone,
-{
-    /// Rotetes the tree rooted by self rightwards.
-    fn rotate_right(mut self: Box<Self>) -> Box<Self> {
-        let Some(mut root) = self.left.take() else {
-            return self;
-        };
-
-        self.left = root.right.take();
-        self.max = Some(self.value.hi())
-            .max(self.left.as_ref().map(|left| left.max))
-            .max(self.right.as_ref().map(|right| right.max))
-            .expect("max with Some should never be None");
-
-        root.right = Some(self);
-        root.max = Some(root.value.hi())
-            .max(root.left.as_ref().map(|left| left.max))
-            .max(root.right.as_ref().map(|right| right.max))
-            .expect("max with Some should never be None");
-
-        root
-    }
-
-    /// Rotetes the tree rooted by self leftwards.
-    fn rotate_left(mut self: Box<Self>) -> Box<Self> {
-        let Some(mut root) = self.right.take() else {
-            return self;
-        };
-
-        self.right = root.left.take();
-        self.max = Some(self.value.hi())
-            .max(self.left.as_ref().map(|left| left.max))
-            .max(self.right.as_ref().map(|right| right.max))
-            .expect("max with Some should never be None");
-
-        root.left = Some(self);
-        root.max = Some(root.value.hi())
-            .max(root.left.as_ref().map(|left| left.max))
-            .max(root.right.as_ref().map(|right| right.max))
-            .expect("max with Some should never be None");
-
-        root
-    }
-}
-
 impl<Intv> IntervalSearchTreeNode<Intv>
 where
     Intv: PartialEq + Interval,
 {
     /// Deletes the given interval from the tree rooted by self.
-    pub fn delete(mut self: Box<Self>, interval: &Intv) -> Option<Box<Self>> {
+    pub fn delete(
+        mut self: Box<Self>,
+        interval: &Intv,
+        tie_break: TieBreakFn<Intv>,
+    ) -> Option<Box<Self>> {
         if &self.value == interval {
             return match (self.left, self.right) {
-                (Some(left), Some(right)) => Some(left.join(right)),
+                (Some(left), Some(right)) => Some(left.join(*right, tie_break)),
                 (left, _) if left.is_some() => left,
                 (_, right) if right.is_some() => right,
                 _ => None,
             };
         }
 
-        if interval.lo() < self.value.lo() {
-            self.left = self.left.map(|left| left.delete(interval)).flatten();
-        } else if interval.lo() > self.value.lo() {
-            self.right = self.right.map(|right| right.delete(interval)).flatten();
+        // Intervals ordering the same as self are always inserted rightwards.
+        if order(interval, &self.value, tie_break).is_lt() {
+            self.left = self.left.and_then(|left| left.delete(interval, tie_break));
+        } else {
+            self.right = self
+                .right
+                .and_then(|right| right.delete(interval, tie_break));
         }
 
+        self.update_max();
         Some(self)
     }
 }
@@ -113,7 +85,10 @@ where
 
     /// Returns true if, and only if, there is an interval in the tree that intersects the given
     /// one.
-    pub fn intersects(&self, interval: &Intv) -> bool {
+    pub fn intersects<Query>(&self, interval: &Query) -> bool
+    where
+        Query: Interval<Bound = Intv::Bound>,
+    {
         if self.value.intersects(interval) {
             return true;
         }
@@ -136,13 +111,18 @@ where
     }
 
     /// Calls the given closure for each interval in the tree overlapping the given one.
-    pub fn for_each_intersection<F>(&self, interval: &Intv, mut f: F)
+    pub fn for_each_intersection<Query, F>(&self, interval: &Query, mut f: F)
     where
+        Query: Interval<Bound = Intv::Bound>,
         F: FnMut(&Intv),
     {
-        fn immersion<Intv, F>(node: &IntervalSearchTreeNode<Intv>, interval: &Intv, f: &mut F)
-        where
+        fn immersion<Intv, Query, F>(
+            node: &IntervalSearchTreeNode<Intv>,
+            interval: &Query,
+            f: &mut F,
+        ) where
             Intv: Interval,
+            Query: Interval<Bound = Intv::Bound>,
             F: FnMut(&Intv),
         {
             if let Some(right) = &node.right {
@@ -187,19 +167,22 @@ where
     }
 
     /// Inserts the given interval in the tree rooted by self.
-    pub fn insert(mut self: Box<Self>, interval: Intv) -> Box<Self> {
+    ///
+    /// Intervals ordering the same as an existing one are placed after it, so ties the given
+    /// function cannot break keep the order in which they were inserted.
+    pub fn insert(mut self: Box<Self>, interval: Intv, tie_break: TieBreakFn<Intv>) -> Box<Self> {
         if self.max < interval.hi() {
             self.max = interval.hi();
         }
 
-        if interval.lo() < self.value.lo() {
+        if order(&interval, &self.value, tie_break).is_lt() {
             if let Some(left) = self.left.take() {
-                self.left = Some(left.insert(interval));
+                self.left = Some(left.insert(interval, tie_break));
             } else {
                 self.left = Some(Box::new(interval.into()));
             }
         } else if let Some(right) = self.right.take() {
-            self.right = Some(right.insert(interval));
+            self.right = Some(right.insert(interval, tie_break));
         } else {
             self.right = Some(Box::new(interval.into()));
         }
@@ -208,41 +191,62 @@ where
     }
 
     /// Given the root of a left (self) and right trees, joins them into a single one.
-    fn join(self: Box<Self>, right: Box<Self>) -> Box<Self> {
-        fn immersion<Intv>(
-            root: Box<IntervalSearchTreeNode<Intv>>,
-            mut intervals: Vec<Intv>,
-        ) -> Box<IntervalSearchTreeNode<Intv>>
-        where
-            Intv: Interval,
-        {
-            if intervals.is_empty() {
-                return root;
-            }
+    fn join(self, right: Self, tie_break: TieBreakFn<Intv>) -> Box<Self> {
+        let mut intervals = self.into_inorder();
+        intervals.extend(right.into_inorder());
+
+        Self::from_inorder(intervals, tie_break)
+            .expect("tree from a non-empty vector should never be None")
+    }
 
-            let center = intervals.len() / 2;
-            let left = intervals.drain(0..center).collect();
-            immersion(immersion(root, left), intervals)
+    /// Builds a balanced tree from the given intervals, which must be in order.
+    ///
+    /// The root of each subtree is the first of the intervals ordering the same as the center
+    /// one, so the tree keeps placing them rightwards.
+    fn from_inorder(mut intervals: Vec<Intv>, tie_break: TieBreakFn<Intv>) -> Option<Box<Self>> {
+        if intervals.is_empty() {
+            return None;
         }
 
-        let mut intervals = self.into_inorder();
-        intervals.extend(right.into_inorder());
+        let mut center = intervals.len() / 2;
+        while center > 0 && order(&intervals[center - 1], &intervals[center], tie_break).is_eq() {
+            center -= 1;
+        }
 
-        let center = intervals.len() / 2;
-        let root = Self::new(intervals.remove(center));
+        let right = intervals.split_off(center + 1);
+        let value = intervals.pop()?;
 
-        immersion(Box::new(root), intervals)
+        let mut root = Box::new(Self::new(value));
+        root.left = Self::from_inorder(intervals, tie_break);
+        root.right = Self::from_inorder(right, tie_break);
+        root.update_max();
+
+        Some(root)
+    }
+
+    /// Recomputes the max bound of self from its value and children.
+    fn update_max(&mut self) {
+        self.max = Some(self.value.hi())
+            .max(self.left.as_ref().map(|left| left.max))
+            .max(self.right.as_ref().map(|right| right.max))
+            .expect("max with Some should never be None");
     }
 
     /// Returns a vector with all the intervals in order.
-    fn into_inorder(self: Box<Self>) -> Vec<Intv> {
-        fn immersion<Intv>(node: Box<IntervalSearchTreeNode<Intv>>, v: &mut Vec<Intv>)
+    fn into_inorder(self) -> Vec<Intv> {
+        fn immersion<Intv>(node: IntervalSearchTreeNode<Intv>, v: &mut Vec<Intv>)
         where
             Intv: Interval,
         {
-            node.left.map(|left| immersion(left, v));
+            if let Some(left) = node.left {
+                immersion(*left, v);
+            }
+
             v.push(node.value);
-            node.right.map(|right| immersion(right, v));
+
+            if let Some(right) = node.right {
+                immersion(*right, v);
+            }
         }
 
         let mut v = Vec::with_capacity(self.count());
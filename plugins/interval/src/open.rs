@@ -0,0 +1,121 @@
+//! Intervals with unbounded ends.
+
+use crate::{Bound, Interval};
+
+/// A [`Bound`] extended with the lowest and highest possible values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Limit<B> {
+    /// Precedes any other limit.
+    Lowest,
+    /// A regular bound.
+    At(B),
+    /// Follows any other limit.
+    Highest,
+}
+
+impl<B> From<B> for Limit<B> {
+    fn from(bound: B) -> Self {
+        Self::At(bound)
+    }
+}
+
+/// An interval whose ends may be unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenInterval<B> {
+    /// The lowest bound, if any.
+    pub lo: Option<B>,
+    /// The highest bound, if any.
+    pub hi: Option<B>,
+}
+
+impl<B> OpenInterval<B> {
+    /// Returns the interval going from the given bound onwards.
+    pub fn since(lo: B) -> Self {
+        Self {
+            lo: Some(lo),
+            hi: None,
+        }
+    }
+
+    /// Returns the interval going up to the given bound.
+    pub fn until(hi: B) -> Self {
+        Self {
+            lo: None,
+            hi: Some(hi),
+        }
+    }
+
+    /// Returns the interval between the given bounds.
+    pub fn between(lo: B, hi: B) -> Self {
+        Self {
+            lo: Some(lo),
+            hi: Some(hi),
+        }
+    }
+}
+
+impl<B> Interval for OpenInterval<B>
+where
+    B: Bound,
+{
+    type Bound = Limit<B>;
+
+    fn lo(&self) -> Self::Bound {
+        self.lo.map(Limit::At).unwrap_or(Limit::Lowest)
+    }
+
+    fn hi(&self) -> Self::Bound {
+        self.hi.map(Limit::At).unwrap_or(Limit::Highest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{open::OpenInterval, IntervalSearchTree};
+
+    #[test]
+    fn open_intervals_intersects_with_tree() {
+        struct Test<'a> {
+            name: &'a str,
+            tree: IntervalSearchTree<OpenInterval<usize>>,
+            query: OpenInterval<usize>,
+            intersects: bool,
+        }
+
+        vec![
+            Test {
+                name: "ongoing interval",
+                tree: IntervalSearchTree::default().with_interval(OpenInterval::since(5)),
+                query: OpenInterval::between(100, 200),
+                intersects: true,
+            },
+            Test {
+                name: "ongoing interval after query",
+                tree: IntervalSearchTree::default().with_interval(OpenInterval::since(5)),
+                query: OpenInterval::between(0, 4),
+                intersects: false,
+            },
+            Test {
+                name: "interval with no beginning",
+                tree: IntervalSearchTree::default().with_interval(OpenInterval::until(5)),
+                query: OpenInterval::between(0, 1),
+                intersects: true,
+            },
+            Test {
+                name: "opposite open intervals",
+                tree: IntervalSearchTree::default().with_interval(OpenInterval::until(5)),
+                query: OpenInterval::since(6),
+                intersects: false,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            assert_eq!(
+                test.tree.intersects(&test.query),
+                test.intersects,
+                "{}",
+                test.name
+            );
+        });
+    }
+}
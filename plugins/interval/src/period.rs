@@ -0,0 +1,625 @@
+//! Periods of time in arbitrary calendars.
+
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use crate::Interval;
+
+/// Determines why a [`CalendarInterval`] could not be parsed.
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum Error {
+    /// Determines that the input does not follow the `year[-month[-day]] [era]` format.
+    #[error("malformed period: {0}")]
+    Syntax(String),
+    /// Determines that the month or day does not exist.
+    #[error("out of range period: {0}")]
+    OutOfRange(String),
+    /// Determines that the era does not match the one of the year.
+    #[error("unknown era: {0}")]
+    Era(String),
+    /// Determines that the input goes on after the period.
+    #[error("unexpected trailing input: {0:?}")]
+    Trailing(String),
+}
+
+/// The structure of a calendar.
+pub trait CalendarSpec {
+    /// Returns the amount of months in the given year.
+    fn months(year: i32) -> u8;
+
+    /// Returns the amount of days in the given month, if it exists.
+    ///
+    /// Every month from 1 to [`CalendarSpec::months`] must exist.
+    fn days(year: i32, month: u8) -> Option<u8>;
+
+    /// Returns the name of the era the given year belongs to, if any.
+    fn era(_year: i32) -> Option<&'static str> {
+        None
+    }
+}
+
+/// The proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy)]
+pub struct Gregorian;
+
+impl Gregorian {
+    /// Returns true if, and only if, the given year has 366 days.
+    fn is_leap(year: i32) -> bool {
+        year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+    }
+}
+
+impl CalendarSpec for Gregorian {
+    fn months(_: i32) -> u8 {
+        12
+    }
+
+    fn days(year: i32, month: u8) -> Option<u8> {
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+            4 | 6 | 9 | 11 => Some(30),
+            2 if Self::is_leap(year) => Some(29),
+            2 => Some(28),
+            _ => None,
+        }
+    }
+}
+
+/// A day in the calendar C.
+///
+/// Days from different calendars are different types, so they can never be compared with each
+/// other.
+pub struct Day<C> {
+    year: i32,
+    month: u8,
+    day: u8,
+    calendar: PhantomData<C>,
+}
+
+impl<C> Day<C> {
+    /// Returns the given day, without checking whether it exists in the calendar.
+    fn unchecked(year: i32, month: u8, day: u8) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            calendar: PhantomData,
+        }
+    }
+
+    /// Returns the year of the day.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month of the day, starting from 1.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month, starting from 1.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl<C> Day<C>
+where
+    C: CalendarSpec,
+{
+    /// Returns the given day, if it exists in the calendar.
+    pub fn new(year: i32, month: u8, day: u8) -> Result<Self, Error> {
+        if day == 0 || C::days(year, month).is_none_or(|days| day > days) {
+            return Err(Error::OutOfRange(format!("{year}-{month:02}-{day:02}")));
+        }
+
+        Ok(Self::unchecked(year, month, day))
+    }
+}
+
+impl<C> std::fmt::Debug for Day<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Day")
+            .field("year", &self.year)
+            .field("month", &self.month)
+            .field("day", &self.day)
+            .finish()
+    }
+}
+
+impl<C> Clone for Day<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for Day<C> {}
+
+impl<C> Eq for Day<C> {}
+
+impl<C> PartialEq for Day<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<C> Ord for Day<C> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.year, self.month, self.day).cmp(&(other.year, other.month, other.day))
+    }
+}
+
+impl<C> PartialOrd for Day<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C> Hash for Day<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.year, self.month, self.day).hash(state);
+    }
+}
+
+/// The unit of time a [`CalendarInterval`] has been defined with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precision {
+    Year,
+    Month,
+    Day,
+}
+
+/// A whole year, month or day of the calendar C.
+pub struct CalendarInterval<C> {
+    lo: Day<C>,
+    hi: Day<C>,
+    precision: Precision,
+    calendar: PhantomData<C>,
+}
+
+/// A whole year, month or day of the [`Gregorian`] calendar.
+pub type Period = CalendarInterval<Gregorian>;
+
+impl<C> std::fmt::Debug for CalendarInterval<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CalendarInterval")
+            .field("lo", &self.lo)
+            .field("hi", &self.hi)
+            .field("precision", &self.precision)
+            .finish()
+    }
+}
+
+impl<C> Clone for CalendarInterval<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for CalendarInterval<C> {}
+
+impl<C> Eq for CalendarInterval<C> {}
+
+impl<C> PartialEq for CalendarInterval<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.lo == other.lo && self.hi == other.hi && self.precision == other.precision
+    }
+}
+
+impl<C> Interval for CalendarInterval<C> {
+    type Bound = Day<C>;
+
+    fn lo(&self) -> Self::Bound {
+        self.lo
+    }
+
+    fn hi(&self) -> Self::Bound {
+        self.hi
+    }
+}
+
+impl<C> CalendarInterval<C>
+where
+    C: CalendarSpec,
+{
+    /// Returns the period spanning the whole given year, if the calendar defines any day in it.
+    pub fn year(year: i32) -> Result<Self, Error> {
+        let month = C::months(year);
+        let days = C::days(year, month).ok_or_else(|| Error::OutOfRange(year.to_string()))?;
+
+        Ok(Self {
+            lo: Day::new(year, 1, 1)?,
+            hi: Day::new(year, month, days)?,
+            precision: Precision::Year,
+            calendar: PhantomData,
+        })
+    }
+
+    /// Returns the period spanning the whole given month, if it exists.
+    pub fn month(year: i32, month: u8) -> Result<Self, Error> {
+        let days =
+            C::days(year, month).ok_or_else(|| Error::OutOfRange(format!("{year}-{month:02}")))?;
+
+        Ok(Self {
+            lo: Day::new(year, month, 1)?,
+            hi: Day::new(year, month, days)?,
+            precision: Precision::Month,
+            calendar: PhantomData,
+        })
+    }
+
+    /// Returns the period spanning the given day, if it exists.
+    pub fn day(year: i32, month: u8, day: u8) -> Result<Self, Error> {
+        let day = Day::new(year, month, day)?;
+
+        Ok(Self {
+            lo: day,
+            hi: day,
+            precision: Precision::Day,
+            calendar: PhantomData,
+        })
+    }
+
+    /// Returns the amount of days in the period.
+    pub fn days(&self) -> u64 {
+        let Day { year, month, .. } = self.lo;
+        match self.precision {
+            Precision::Year => (1..=C::months(year))
+                .filter_map(|month| C::days(year, month))
+                .map(u64::from)
+                .sum(),
+            Precision::Month => C::days(year, month).map(u64::from).unwrap_or_default(),
+            Precision::Day => 1,
+        }
+    }
+}
+
+impl<C> CalendarInterval<C> {
+    /// Returns the unit of time the period has been defined with.
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// Returns true if, and only if, the given period is fully contained in self.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.lo <= other.lo && other.hi <= self.hi
+    }
+}
+
+impl<C> FromStr for CalendarInterval<C>
+where
+    C: CalendarSpec,
+{
+    type Err = Error;
+
+    /// Parses a period in the `year[-month[-day]] [era]` format, where the year may be negative.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, era) = match s.split_once(' ') {
+            Some((date, era)) => (date, Some(era)),
+            None => (s, None),
+        };
+
+        if era.is_some_and(|era| era.is_empty() || era.ends_with(char::is_whitespace)) {
+            return Err(Error::Trailing(s.to_string()));
+        }
+
+        let (sign, unsigned) = match date.strip_prefix('-') {
+            Some(unsigned) => (-1, unsigned),
+            None => (1, date),
+        };
+
+        let components = unsigned
+            .split('-')
+            // A leading plus sign is not part of the format, even if u32 accepts it.
+            .map(|component| {
+                component
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|_| !component.starts_with('+'))
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::Syntax(s.to_string()))?;
+
+        let year = components
+            .first()
+            .and_then(|&year| i32::try_from(year).ok())
+            .map(|year| sign * year)
+            .ok_or_else(|| Error::Syntax(s.to_string()))?;
+
+        if era.is_some() && era != C::era(year) {
+            return Err(Error::Era(s.to_string()));
+        }
+
+        let component = |index: usize| {
+            u8::try_from(components[index]).map_err(|_| Error::OutOfRange(s.to_string()))
+        };
+
+        match components.len() {
+            1 => Self::year(year),
+            2 => Self::month(year, component(1)?),
+            3 => Self::day(year, component(1)?, component(2)?),
+            _ => return Err(Error::Syntax(s.to_string())),
+        }
+        .map_err(|_| Error::OutOfRange(s.to_string()))
+    }
+}
+
+impl<C> Display for CalendarInterval<C>
+where
+    C: CalendarSpec,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Day {
+            year, month, day, ..
+        } = self.lo;
+        match self.precision {
+            Precision::Year => write!(f, "{year}"),
+            Precision::Month => write!(f, "{year}-{month:02}"),
+            Precision::Day => write!(f, "{year}-{month:02}-{day:02}"),
+        }?;
+
+        if let Some(era) = C::era(year) {
+            write!(f, " {era}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalendarInterval, CalendarSpec, Day, Error, Gregorian, Period};
+
+    /// A calendar of 13 months of 28 days each, counting years after the founding of a realm.
+    struct Realm;
+
+    impl CalendarSpec for Realm {
+        fn months(_: i32) -> u8 {
+            13
+        }
+
+        fn days(_: i32, month: u8) -> Option<u8> {
+            (1..=13).contains(&month).then_some(28)
+        }
+
+        fn era(year: i32) -> Option<&'static str> {
+            (year > 0).then_some("AR")
+        }
+    }
+
+    #[test]
+    fn period_from_str() {
+        struct Test<'a> {
+            name: &'a str,
+            input: &'a str,
+            output: Result<Period, Error>,
+            days: u64,
+        }
+
+        vec![
+            Test {
+                name: "whole year",
+                input: "1023",
+                output: Ok(Period::year(1023).unwrap()),
+                days: 365,
+            },
+            Test {
+                name: "february of a leap year",
+                input: "2000-02",
+                output: Ok(Period::month(2000, 2).unwrap()),
+                days: 29,
+            },
+            Test {
+                name: "february of a centennial year",
+                input: "1900-02",
+                output: Ok(Period::month(1900, 2).unwrap()),
+                days: 28,
+            },
+            Test {
+                name: "day before the common era",
+                input: "-44-03-15",
+                output: Ok(Period::day(-44, 3, 15).unwrap()),
+                days: 1,
+            },
+            Test {
+                name: "month out of range",
+                input: "1023-13",
+                output: Err(Error::OutOfRange("1023-13".into())),
+                days: 0,
+            },
+            Test {
+                name: "day out of range",
+                input: "1023-02-29",
+                output: Err(Error::OutOfRange("1023-02-29".into())),
+                days: 0,
+            },
+            Test {
+                name: "too many components",
+                input: "1023-05-01-01",
+                output: Err(Error::Syntax("1023-05-01-01".into())),
+                days: 0,
+            },
+            Test {
+                name: "explicit positive sign",
+                input: "+1023",
+                output: Err(Error::Syntax("+1023".into())),
+                days: 0,
+            },
+            Test {
+                name: "explicit positive month",
+                input: "1023-+5",
+                output: Err(Error::Syntax("1023-+5".into())),
+                days: 0,
+            },
+            Test {
+                name: "month name",
+                input: "May 1023",
+                output: Err(Error::Syntax("May 1023".into())),
+                days: 0,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let period = test.input.parse::<Period>();
+            assert_eq!(period, test.output, "{}", test.name);
+
+            if let Ok(period) = period {
+                assert_eq!(period.days(), test.days, "{}", test.name);
+                assert_eq!(period.to_string(), test.input, "{}", test.name);
+            }
+        });
+    }
+
+    #[test]
+    fn period_contains() {
+        let year = Period::year(1023).unwrap();
+        let month = Period::month(1023, 5).unwrap();
+        let day = Period::day(1023, 5, 17).unwrap();
+
+        assert!(year.contains(&month), "year should contain its months");
+        assert!(month.contains(&day), "month should contain its days");
+        assert!(!day.contains(&month), "day should not contain its month");
+        assert!(
+            !Period::year(1024).unwrap().contains(&day),
+            "year should not contain days from other years"
+        );
+    }
+
+    #[test]
+    fn calendar_interval_from_str() {
+        struct Test<'a> {
+            name: &'a str,
+            input: &'a str,
+            output: Result<CalendarInterval<Realm>, Error>,
+            display: &'a str,
+            days: u64,
+        }
+
+        vec![
+            Test {
+                name: "year with era",
+                input: "1023 AR",
+                output: Ok(CalendarInterval::year(1023).unwrap()),
+                display: "1023 AR",
+                days: 364,
+            },
+            Test {
+                name: "last month of the year",
+                input: "1023-13",
+                output: Ok(CalendarInterval::month(1023, 13).unwrap()),
+                display: "1023-13 AR",
+                days: 28,
+            },
+            Test {
+                name: "day before the era",
+                input: "-3-13-28",
+                output: Ok(CalendarInterval::day(-3, 13, 28).unwrap()),
+                display: "-3-13-28",
+                days: 1,
+            },
+            Test {
+                name: "day out of range",
+                input: "1023-13-29",
+                output: Err(Error::OutOfRange("1023-13-29".into())),
+                display: "",
+                days: 0,
+            },
+            Test {
+                name: "trailing space",
+                input: "1023 ",
+                output: Err(Error::Trailing("1023 ".into())),
+                display: "",
+                days: 0,
+            },
+            Test {
+                name: "trailing space after the era",
+                input: "1023 AR ",
+                output: Err(Error::Trailing("1023 AR ".into())),
+                display: "",
+                days: 0,
+            },
+            Test {
+                name: "year out of the era",
+                input: "-3 AR",
+                output: Err(Error::Era("-3 AR".into())),
+                display: "",
+                days: 0,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let period = test.input.parse::<CalendarInterval<Realm>>();
+            assert_eq!(period, test.output, "{}", test.name);
+
+            if let Ok(period) = period {
+                assert_eq!(period.days(), test.days, "{}", test.name);
+                assert_eq!(period.to_string(), test.display, "{}", test.name);
+            }
+        });
+    }
+
+    #[test]
+    fn day_new() {
+        struct Test<'a> {
+            name: &'a str,
+            day: (i32, u8, u8),
+            exists: bool,
+        }
+
+        vec![
+            Test {
+                name: "leap day",
+                day: (2000, 2, 29),
+                exists: true,
+            },
+            Test {
+                name: "leap day of a common year",
+                day: (1900, 2, 29),
+                exists: false,
+            },
+            Test {
+                name: "day zero",
+                day: (1023, 5, 0),
+                exists: false,
+            },
+            Test {
+                name: "month out of range",
+                day: (1023, 13, 40),
+                exists: false,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let (year, month, day) = test.day;
+            let output = Day::<Gregorian>::new(year, month, day);
+
+            assert_eq!(output.is_ok(), test.exists, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn year_without_days() {
+        /// A calendar with no months at all.
+        struct Void;
+
+        impl CalendarSpec for Void {
+            fn months(_: i32) -> u8 {
+                0
+            }
+
+            fn days(_: i32, _: u8) -> Option<u8> {
+                None
+            }
+        }
+
+        assert_eq!(
+            CalendarInterval::<Void>::year(1023),
+            Err(Error::OutOfRange("1023".into())),
+            "years without days should be out of range"
+        );
+    }
+}
@@ -1,8 +1,13 @@
 //! The plugin implementation for [`IntervalSearchTree`].
 
-use std::marker::PhantomData;
+use std::{cmp::Ordering, collections::BTreeMap};
 
-use alvidir::{prelude::*, property::Extract};
+use alvidir::{
+    graph::Graph,
+    prelude::*,
+    property::Extract,
+    schema::transaction::{Changeset, Operation},
+};
 
 use crate::{Interval, IntervalSearchTree};
 
@@ -38,107 +43,343 @@ where
     }
 }
 
-impl<Id, Intv> Identify for NodeInterval<Id, Intv> {
-    type Id = Id;
-
-    fn id(&self) -> &Self::Id {
-        &self.node_id
-    }
+/// Decides the order of the intervals sharing the same lower bound in an [`IntervalIndex`].
+///
+/// Whatever the policy, intervals it cannot tell apart keep the order in which they were indexed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// In the order they were indexed.
+    #[default]
+    Sequence,
+    /// By the id of their node.
+    Id,
+    /// By their higher bound, the shortest interval first.
+    Shortest,
+    /// By their higher bound, the longest interval first.
+    Longest,
 }
 
-type SearchTree<Id, Intv> = IntervalSearchTree<NodeInterval<Id, Intv>>;
+/// The resource indexing the intervals of each node in the schema.
+pub struct IntervalIndex<Id, Intv>
+where
+    Intv: Interval,
+{
+    /// All the intervals in the schema.
+    search_tree: IntervalSearchTree<NodeInterval<Id, Intv>>,
+    /// The intervals each node has been indexed with.
+    nodes: BTreeMap<Id, Vec<Intv>>,
+}
 
-/// Implements the [`Plugin`] trait for an arbitrary extractor of intervals from a source of type T.
-pub struct IntervalPlugin<T, Extractor> {
-    extractor: Extractor,
-    node: PhantomData<T>,
+impl<Id, Intv> Default for IntervalIndex<Id, Intv>
+where
+    Intv: Interval,
+{
+    fn default() -> Self {
+        Self {
+            search_tree: Default::default(),
+            nodes: Default::default(),
+        }
+    }
 }
 
-impl<T, Extractor> IntervalPlugin<T, Extractor>
+impl<Id, Intv> IntervalIndex<Id, Intv>
 where
-    T: 'static + Identify,
-    T::Id: Clone + PartialEq,
-    Extractor: 'static + Extract<T>,
-    Extractor::Target: Interval + PartialEq,
+    Intv: Interval,
 {
-    fn on_delete(
-        _: Ctx<T>,
-        target: Target<T>,
-        search_tree: Res<SearchTree<T::Id, Extractor::Target>>,
-        extractor: Res<Extractor>,
-    ) -> Result<()> {
-        let Some(intervals) = (target, extractor).with(|(target, factory)| {
-            factory.all(target).into_iter().map({
-                let node_id = target.id().clone();
-                move |interval| NodeInterval {
-                    node_id: node_id.clone(),
-                    interval,
-                }
-            })
-        }) else {
-            return Ok(());
-        };
+    /// Returns true if, and only if, any node has an interval intersecting the given one.
+    pub fn intersects<Query>(&self, interval: &Query) -> bool
+    where
+        Query: Interval<Bound = Intv::Bound>,
+    {
+        self.search_tree.intersects(interval)
+    }
 
-        search_tree.with_mut(|search_tree| {
-            intervals.into_iter().for_each(|interval| {
-                search_tree.delete(&interval);
+    /// Calls the given closure for each node's interval overlapping the given one, from the last
+    /// interval in order to the first.
+    pub fn for_each_intersection<Query, F>(&self, interval: &Query, mut f: F)
+    where
+        Query: Interval<Bound = Intv::Bound>,
+        F: FnMut(&Id, &Intv),
+    {
+        self.search_tree
+            .for_each_intersection(interval, |node_interval| {
+                f(&node_interval.node_id, &node_interval.interval)
             });
-        });
-
-        Ok(())
     }
 }
 
-impl<T, Extractor> IntervalPlugin<T, Extractor>
+impl<Id, Intv> IntervalIndex<Id, Intv>
 where
-    T: 'static + Identify,
-    T::Id: Clone + PartialEq,
-    Extractor: 'static + Extract<T>,
-    Extractor::Target: Interval + PartialEq,
+    Id: Ord + Clone,
+    Intv: Interval + PartialEq + Clone,
 {
-    fn on_save(
-        _: Ctx<T>,
-        target: Target<T>,
-        search_tree: Res<SearchTree<T::Id, Extractor::Target>>,
-        extractor: Res<Extractor>,
-    ) -> Result<()> {
-        let Some(intervals) = (target, extractor).with(|(target, extractor)| {
-            extractor.all(target).into_iter().map({
-                let node_id = target.id().clone();
-                move |interval| NodeInterval {
-                    node_id: node_id.clone(),
-                    interval,
-                }
+    /// Orders the intervals sharing the same lower bound by the given policy.
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        let tie_break: fn(&NodeInterval<Id, Intv>, &NodeInterval<Id, Intv>) -> Ordering =
+            match tie_break {
+                TieBreak::Sequence => |_, _| Ordering::Equal,
+                TieBreak::Id => |a, b| a.node_id.cmp(&b.node_id),
+                TieBreak::Shortest => |a, b| a.hi().cmp(&b.hi()),
+                TieBreak::Longest => |a, b| b.hi().cmp(&a.hi()),
+            };
+
+        self.search_tree = self.search_tree.with_tie_break(tie_break);
+        self
+    }
+
+    /// Indexes the given intervals for the node with the given id, replacing the old ones.
+    fn insert(&mut self, node_id: Id, intervals: Vec<Intv>) {
+        self.remove(&node_id);
+
+        intervals.iter().cloned().for_each(|interval| {
+            self.search_tree.insert(NodeInterval {
+                node_id: node_id.clone(),
+                interval,
             })
-        }) else {
-            return Ok(());
+        });
+
+        self.nodes.insert(node_id, intervals);
+    }
+
+    /// Removes all the intervals of the node with the given id.
+    fn remove(&mut self, node_id: &Id) {
+        let Some(intervals) = self.nodes.remove(node_id) else {
+            return;
         };
 
-        search_tree.with_mut(|search_tree| {
-            intervals.into_iter().for_each(|interval| {
-                search_tree.insert(interval);
-            });
+        intervals.into_iter().for_each(|interval| {
+            self.search_tree.delete(&NodeInterval {
+                node_id: node_id.clone(),
+                interval,
+            })
         });
+    }
+}
+
+/// Implements the [`Plugin`] trait for an arbitrary extractor of intervals from a source of type T.
+///
+/// The [`IntervalIndex`] is updated when transactions are committed, never before, so aborted
+/// transactions cannot leave stale intervals behind.
+pub struct IntervalPlugin<Extractor> {
+    extractor: Extractor,
+    tie_break: TieBreak,
+}
+
+impl<Extractor> IntervalPlugin<Extractor> {
+    /// Returns a plugin indexing the intervals retrieved by the given extractor.
+    pub fn new(extractor: Extractor) -> Self {
+        Self {
+            extractor,
+            tie_break: TieBreak::default(),
+        }
+    }
 
-        Ok(())
+    /// Orders the indexed intervals sharing the same lower bound by the given policy.
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
     }
 }
 
-impl<T, Extractor> Plugin<T> for IntervalPlugin<T, Extractor>
+impl<T, Extractor> Plugin<T> for IntervalPlugin<Extractor>
 where
     T: 'static + Identify,
-    T::Id: Clone + PartialEq,
+    T::Id: Ord + Clone,
     Extractor: 'static + Extract<T>,
-    Extractor::Target: Interval + PartialEq,
+    Extractor::Target: 'static + Interval + PartialEq + Clone,
 {
     fn install(self, schema: Schema<T>) -> Schema<T>
     where
         T: Identify,
     {
+        let extractor = self.extractor;
+
+        let mut index =
+            IntervalIndex::<T::Id, Extractor::Target>::default().with_tie_break(self.tie_break);
+        // A graph refusing to be read has already been reported by its poison policy.
+        if let Ok(graph) = schema.read() {
+            graph.into_iter().for_each(|node| {
+                index.insert(node.id().clone(), extractor.all(node));
+            });
+        }
+
+        let schema = schema.with_resource(index);
+        let index = Res::<IntervalIndex<T::Id, Extractor::Target>>::from(schema.resources());
+
+        schema.with_subscriber(move |changeset: &Changeset<T>, _: &Graph<T>| {
+            index.with_mut(|index| {
+                changeset.iter().for_each(|op| match op {
+                    Operation::Save(node) => index.insert(node.id().clone(), extractor.all(node)),
+                    Operation::Delete(node_id) => index.remove(node_id),
+                })
+            });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alvidir::{
+        deref::With, graph::Graph, id::Identify, property::Extract, schema::resource::Res,
+        schema::transaction::Transaction, schema::Schema,
+    };
+
+    use crate::{IntervalIndex, IntervalPlugin, OpenInterval, TieBreak};
+
+    #[derive(Debug, Clone)]
+    struct Node {
+        id: usize,
+        interval: (usize, usize),
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    struct NodeIntervalExtractor;
+
+    impl Extract<Node> for NodeIntervalExtractor {
+        type Target = OpenInterval<usize>;
+
+        fn all(&self, source: &Node) -> Vec<Self::Target> {
+            vec![OpenInterval::between(source.interval.0, source.interval.1)]
+        }
+    }
+
+    fn overlapping(schema: &Schema<Node>, lo: usize, hi: usize) -> Vec<usize> {
+        Res::<IntervalIndex<usize, OpenInterval<usize>>>::from(schema.resources())
+            .with(|index| {
+                let mut node_ids = Vec::new();
+                index.for_each_intersection(&OpenInterval::between(lo, hi), |node_id, _| {
+                    node_ids.push(*node_id)
+                });
+
+                node_ids.sort();
+                node_ids
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn index_should_follow_committed_changes() {
+        let schema = Schema::from(Graph::from_iter(vec![Node {
+            id: 1,
+            interval: (0, 5),
+        }]))
+        .install(IntervalPlugin::new(NodeIntervalExtractor));
+
+        assert_eq!(
+            overlapping(&schema, 4, 4),
+            vec![1],
+            "existing nodes should be indexed on install"
+        );
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.save(Node {
+                    id: 1,
+                    interval: (6, 9),
+                })?;
+                ctx.save(Node {
+                    id: 2,
+                    interval: (3, 7),
+                })?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            overlapping(&schema, 4, 4),
+            vec![2],
+            "updated nodes should not keep their old intervals"
+        );
+
+        assert_eq!(overlapping(&schema, 7, 7), vec![1, 2]);
+
         schema
-            .with_resource(self.extractor)
-            .with_resource(SearchTree::<T, Extractor::Target>::default())
-            .with_trigger(AfterSave, Self::on_save)
-            .with_trigger(AfterDelete, Self::on_delete)
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(2)?;
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            overlapping(&schema, 7, 7),
+            vec![1],
+            "deleted nodes should be dropped"
+        );
+    }
+
+    #[test]
+    fn index_should_follow_tie_break() {
+        struct Test<'a> {
+            name: &'a str,
+            tie_break: TieBreak,
+            output: Vec<usize>,
+        }
+
+        vec![
+            Test {
+                name: "by sequence",
+                tie_break: TieBreak::Sequence,
+                output: vec![1, 3, 2],
+            },
+            Test {
+                name: "by id",
+                tie_break: TieBreak::Id,
+                output: vec![3, 2, 1],
+            },
+            Test {
+                name: "shortest first",
+                tie_break: TieBreak::Shortest,
+                output: vec![2, 1, 3],
+            },
+            Test {
+                name: "longest first",
+                tie_break: TieBreak::Longest,
+                output: vec![3, 1, 2],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let schema = Schema::from(Graph::default())
+                .install(IntervalPlugin::new(NodeIntervalExtractor).with_tie_break(test.tie_break));
+
+            schema
+                .transaction()
+                .with(|ctx| {
+                    ctx.save(Node {
+                        id: 2,
+                        interval: (1, 6),
+                    })?;
+                    ctx.save(Node {
+                        id: 3,
+                        interval: (1, 2),
+                    })?;
+                    ctx.save(Node {
+                        id: 1,
+                        interval: (1, 4),
+                    })?;
+                    Ok(())
+                })
+                .expect("transaction should not fail");
+
+            let mut node_ids = Vec::new();
+            Res::<IntervalIndex<usize, OpenInterval<usize>>>::from(schema.resources())
+                .with(|index| {
+                    index.for_each_intersection(&OpenInterval::between(0, 9), |node_id, _| {
+                        node_ids.push(*node_id)
+                    })
+                })
+                .expect("index should be readable");
+
+            assert_eq!(node_ids, test.output, "{}", test.name);
+        })
     }
 }
@@ -1,6 +1,11 @@
 //! The interval search tree (IST) definition.
 
-use crate::{node::IntervalSearchTreeNode, Interval};
+use std::cmp::Ordering;
+
+use crate::{
+    node::{IntervalSearchTreeNode, TieBreakFn},
+    Interval,
+};
 
 /// An interval search tree.
 pub struct IntervalSearchTree<Intv>
@@ -8,6 +13,8 @@ where
     Intv: Interval,
 {
     root: Option<Box<IntervalSearchTreeNode<Intv>>>,
+    /// Orders the intervals sharing the same lower bound.
+    tie_break: TieBreakFn<Intv>,
 }
 
 impl<Intv> Default for IntervalSearchTree<Intv>
@@ -17,6 +24,7 @@ where
     fn default() -> Self {
         Self {
             root: Default::default(),
+            tie_break: |_, _| Ordering::Equal,
         }
     }
 }
@@ -28,7 +36,7 @@ where
     /// Deletes the given interval from the tree.
     pub fn delete(&mut self, id: &Intv) {
         if let Some(root) = self.root.take() {
-            self.root = root.delete(id);
+            self.root = root.delete(id, self.tie_break);
         }
     }
 }
@@ -37,6 +45,15 @@ impl<Intv> IntervalSearchTree<Intv>
 where
     Intv: Interval,
 {
+    /// Orders the intervals sharing the same lower bound by the given function.
+    ///
+    /// By default, and for those intervals the function considers equal, the tree keeps them in
+    /// the order they were inserted.
+    pub fn with_tie_break(mut self, tie_break: TieBreakFn<Intv>) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
     /// Inserts the given interval in the tree.
     pub fn with_interval(mut self, interval: Intv) -> Self {
         self.insert(interval);
@@ -46,7 +63,7 @@ where
     /// Inserts the given interval in the tree.
     pub fn insert(&mut self, interval: Intv) {
         if let Some(root) = self.root.take() {
-            self.root = Some(root.insert(interval));
+            self.root = Some(root.insert(interval, self.tie_break));
             return;
         }
 
@@ -55,21 +72,26 @@ where
 
     /// Returns true if, and only if, there is an interval in the tree that intersects the given
     /// one.
-    pub fn intersects(&self, interval: &Intv) -> bool {
+    pub fn intersects<Query>(&self, interval: &Query) -> bool
+    where
+        Query: Interval<Bound = Intv::Bound>,
+    {
         self.root
             .as_ref()
             .map(|root| root.intersects(interval))
             .unwrap_or_default()
     }
 
-    /// Calls the given closure for each interval in the tree overlapping the given one.
-    pub fn for_each_intersection<F>(&self, interval: &Intv, f: F)
+    /// Calls the given closure for each interval in the tree overlapping the given one, from the
+    /// last interval in order to the first.
+    pub fn for_each_intersection<Query, F>(&self, interval: &Query, f: F)
     where
+        Query: Interval<Bound = Intv::Bound>,
         F: FnMut(&Intv),
     {
-        self.root
-            .as_ref()
-            .map(|root| root.for_each_intersection(interval, f));
+        if let Some(root) = &self.root {
+            root.for_each_intersection(interval, f);
+        }
     }
 }
 
@@ -77,7 +99,7 @@ where
 mod tests {
     use crate::{
         fixtures::{interval_mock, IntervalMock},
-        IntervalSearchTree,
+        Interval, IntervalSearchTree,
     };
 
     #[test]
@@ -130,6 +152,21 @@ mod tests {
                 query: interval_mock!(1, 2),
                 intersects: true,
             },
+            Test {
+                name: "after deleting the interval with the highest bound",
+                tree: {
+                    let mut tree = IntervalSearchTree::default()
+                        .with_interval(interval_mock!(5, 6))
+                        .with_interval(interval_mock!(2, 3))
+                        .with_interval(interval_mock!(3, 10))
+                        .with_interval(interval_mock!(7, 8));
+
+                    tree.delete(&interval_mock!(3, 10));
+                    tree
+                },
+                query: interval_mock!(7, 7),
+                intersects: true,
+            },
         ]
         .into_iter()
         .for_each(|test| {
@@ -200,4 +237,148 @@ mod tests {
             });
         })
     }
+
+    #[test]
+    fn delete_from_tree() {
+        struct Test<'a> {
+            name: &'a str,
+            tree: IntervalSearchTree<IntervalMock<usize>>,
+            delete: IntervalMock<usize>,
+            output: Vec<IntervalMock<usize>>,
+        }
+
+        vec![
+            Test {
+                name: "distinct lower bounds",
+                tree: IntervalSearchTree::default()
+                    .with_interval(interval_mock!(2, 3))
+                    .with_interval(interval_mock!(1, 4)),
+                delete: interval_mock!(1, 4),
+                output: vec![interval_mock!(2, 3)],
+            },
+            Test {
+                name: "same lower bound",
+                tree: IntervalSearchTree::default()
+                    .with_interval(interval_mock!(1, 2))
+                    .with_interval(interval_mock!(1, 5))
+                    .with_interval(interval_mock!(1, 3)),
+                delete: interval_mock!(1, 3),
+                output: vec![interval_mock!(1, 5), interval_mock!(1, 2)],
+            },
+            Test {
+                name: "node with both children",
+                tree: IntervalSearchTree::default()
+                    .with_interval(interval_mock!(5, 6))
+                    .with_interval(interval_mock!(0, 4))
+                    .with_interval(interval_mock!(7, 9)),
+                delete: interval_mock!(5, 6),
+                output: vec![interval_mock!(7, 9), interval_mock!(0, 4)],
+            },
+            Test {
+                name: "missing interval",
+                tree: IntervalSearchTree::default().with_interval(interval_mock!(1, 2)),
+                delete: interval_mock!(1, 3),
+                output: vec![interval_mock!(1, 2)],
+            },
+        ]
+        .into_iter()
+        .for_each(|mut test| {
+            test.tree.delete(&test.delete);
+
+            let mut intervals = Vec::default();
+            test.tree
+                .for_each_intersection(&interval_mock!(0, 9), |interval| {
+                    intervals.push(interval.clone())
+                });
+
+            assert_eq!(intervals, test.output, "{}", test.name);
+        })
+    }
+
+    #[test]
+    fn tie_break_in_tree() {
+        struct Test<'a> {
+            name: &'a str,
+            tree: IntervalSearchTree<IntervalMock<usize>>,
+            output: Vec<IntervalMock<usize>>,
+        }
+
+        vec![
+            Test {
+                name: "insertion order by default",
+                tree: IntervalSearchTree::default()
+                    .with_interval(interval_mock!(1, 5))
+                    .with_interval(interval_mock!(1, 2))
+                    .with_interval(interval_mock!(1, 3)),
+                output: vec![
+                    interval_mock!(1, 3),
+                    interval_mock!(1, 2),
+                    interval_mock!(1, 5),
+                ],
+            },
+            Test {
+                name: "custom tie break",
+                tree: IntervalSearchTree::<IntervalMock<usize>>::default()
+                    .with_tie_break(|a, b| a.hi().cmp(&b.hi()))
+                    .with_interval(interval_mock!(1, 5))
+                    .with_interval(interval_mock!(1, 2))
+                    .with_interval(interval_mock!(1, 3)),
+                output: vec![
+                    interval_mock!(1, 5),
+                    interval_mock!(1, 3),
+                    interval_mock!(1, 2),
+                ],
+            },
+            Test {
+                name: "insertion order after rebuilding the tree",
+                tree: {
+                    let mut tree = IntervalSearchTree::default()
+                        .with_interval(interval_mock!(2, 2))
+                        .with_interval(interval_mock!(1, 5))
+                        .with_interval(interval_mock!(1, 2))
+                        .with_interval(interval_mock!(3, 3))
+                        .with_interval(interval_mock!(1, 3));
+
+                    tree.delete(&interval_mock!(2, 2));
+                    tree
+                },
+                output: vec![
+                    interval_mock!(3, 3),
+                    interval_mock!(1, 3),
+                    interval_mock!(1, 2),
+                    interval_mock!(1, 5),
+                ],
+            },
+            Test {
+                name: "deleting ties after rebuilding the tree",
+                tree: {
+                    let mut tree = IntervalSearchTree::default()
+                        .with_interval(interval_mock!(2, 2))
+                        .with_interval(interval_mock!(1, 5))
+                        .with_interval(interval_mock!(1, 2))
+                        .with_interval(interval_mock!(3, 3))
+                        .with_interval(interval_mock!(1, 3));
+
+                    tree.delete(&interval_mock!(2, 2));
+                    tree.delete(&interval_mock!(1, 3));
+                    tree
+                },
+                output: vec![
+                    interval_mock!(3, 3),
+                    interval_mock!(1, 2),
+                    interval_mock!(1, 5),
+                ],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let mut intervals = Vec::default();
+            test.tree
+                .for_each_intersection(&interval_mock!(0, 9), |interval| {
+                    intervals.push(interval.clone())
+                });
+
+            assert_eq!(intervals, test.output, "{}", test.name);
+        })
+    }
 }